@@ -34,13 +34,13 @@ impl TraceMapper for MyMapper {
     type EventData = String;
     type TaskData = String;
 
-    fn map_event(event: &tracing::Event<'_>) -> String {
+    fn map_event(event: &tracing::Event<'_>) -> Option<String> {
         let mut message = String::new();
         event.record(&mut MessageVisitor(&mut message));
-        message
+        Some(message)
     }
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
-        attrs.metadata().name().to_string()
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
+        Some(attrs.metadata().name().to_string())
     }
 }
 
@@ -1,7 +1,8 @@
 //! Multi-level task hierarchy with per-variant rendering and colored event levels.
 //!
 //! A three-tier `TaskData` enum (Pipeline → Stage → Step) where each variant
-//! controls its own subtree via `render_task` dispatch. A custom `EventData`
+//! controls its own line via `render_task_line` dispatch, and completed stages
+//! collapse into a summary line via `Renderer::collapse`. A custom `EventData`
 //! carries semantic levels for colored output. See `tokio.rs` for the async
 //! channel pattern used here.
 
@@ -61,104 +62,106 @@ impl Renderer for CiRenderer {
         self.tick = (self.tick + 1) % SPINNER.len();
     }
 
-    // Dispatch to per-variant methods so each level controls its own subtree.
-    fn render_task(
+    // Completed stages fold into one summary line; the default traversal
+    // skips their steps and events.
+    fn collapse(&self, task: &TaskView<'_, Self>) -> Collapse {
+        match task.data() {
+            TaskData::Stage { .. } => Collapse::Always,
+            _ => Collapse::Never,
+        }
+    }
+
+    // Dispatch to per-variant methods so each level controls its own line.
+    fn render_task_line(
         &mut self,
         f: &mut FrameWriter<'_>,
         task: &TaskView<'_, Self>,
     ) -> std::io::Result<()> {
         match task.data() {
-            TaskData::Pipeline { .. } => self.render_pipeline(f, task),
-            TaskData::Stage { .. } => self.render_stage(f, task),
-            TaskData::Step { .. } => self.render_step(f, task),
+            TaskData::Pipeline { name } => self.render_pipeline(f, task, name),
+            TaskData::Stage { name } => self.render_stage(f, task, name),
+            TaskData::Step { name } => self.render_step(f, task, name),
+        }
+    }
+
+    fn render_summary_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+        summary: &TaskSummary,
+    ) -> std::io::Result<()> {
+        let TaskData::Stage { name } = task.data() else {
+            return Ok(());
+        };
+        writeln!(
+            f,
+            "  {} {} {name} {}",
+            "✔".green(),
+            stage_label(task).dimmed(),
+            summary.dimmed(),
+        )
+    }
+
+    fn render_event_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        event: &EventView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let data = event.data();
+        let msg = &data.message;
+        match data.level {
+            Level::Info => writeln!(f, "      {} {}", "│".dimmed(), msg.dimmed()),
+            Level::Warn => writeln!(f, "      {} {}", "│".yellow(), msg.yellow()),
+            Level::Error => writeln!(f, "      {} {}", "│".red(), msg.red().bold()),
+            Level::Success => writeln!(f, "      {} {}", "│".green(), msg.green()),
         }
     }
 }
 
+// task.index() gives position among siblings — used for "[1/7]" labels.
+fn stage_label(task: &TaskView<'_, CiRenderer>) -> String {
+    format!("[{}/{TOTAL_STAGES}]", task.index() + 1)
+}
+
 impl CiRenderer {
     fn render_pipeline(
         &mut self,
         f: &mut FrameWriter<'_>,
         task: &TaskView<'_, CiRenderer>,
+        name: &str,
     ) -> std::io::Result<()> {
-        let TaskData::Pipeline { name } = task.data() else {
-            return Ok(());
-        };
         if task.active() {
-            writeln!(f, "{} {}", SPINNER[self.tick].magenta(), name.bold())?;
+            writeln!(f, "{} {}", SPINNER[self.tick].magenta(), name.bold())
         } else {
-            writeln!(f, "{} {}", "✔".green().bold(), name.bold())?;
+            writeln!(f, "{} {}", "✔".green().bold(), name.bold())
         }
-        for sub in task.subtasks() {
-            self.render_task(f, &sub)?;
-        }
-        Ok(())
     }
 
     fn render_stage(
         &mut self,
         f: &mut FrameWriter<'_>,
         task: &TaskView<'_, CiRenderer>,
+        name: &str,
     ) -> std::io::Result<()> {
-        let TaskData::Stage { name } = task.data() else {
-            return Ok(());
-        };
-        // task.index() gives position among siblings — used for "[1/7]" labels.
-        let idx = task.index() + 1;
-        let label = format!("[{idx}/{TOTAL_STAGES}]");
-        if !task.active() {
-            // Completed stages collapse — no subtasks or events shown.
-            writeln!(f, "  {} {} {name}", "✔".green(), label.dimmed())?;
-        } else {
-            writeln!(
-                f,
-                "  {} {} {}",
-                SPINNER[self.tick].magenta(),
-                label.dimmed(),
-                name.cyan(),
-            )?;
-            for sub in task.subtasks() {
-                self.render_task(f, &sub)?;
-            }
-        }
-        Ok(())
+        writeln!(
+            f,
+            "  {} {} {}",
+            SPINNER[self.tick].magenta(),
+            stage_label(task).dimmed(),
+            name.cyan(),
+        )
     }
 
     fn render_step(
         &mut self,
         f: &mut FrameWriter<'_>,
         task: &TaskView<'_, CiRenderer>,
+        name: &str,
     ) -> std::io::Result<()> {
-        let TaskData::Step { name } = task.data() else {
-            return Ok(());
-        };
         if !task.active() {
-            writeln!(f, "    {} {name}", "✔".green().dimmed())?;
+            writeln!(f, "    {} {name}", "✔".green().dimmed())
         } else {
-            writeln!(f, "    {} {}", SPINNER[self.tick].magenta(), name.dimmed())?;
-            // Show last 3 events as a tail window (same pattern as tokio.rs).
-            for event in task.events().rev().take(3).rev() {
-                self.render_event(f, &event)?;
-            }
-            for sub in task.subtasks() {
-                self.render_task(f, &sub)?;
-            }
-        }
-        Ok(())
-    }
-
-    fn render_event(
-        &mut self,
-        f: &mut FrameWriter<'_>,
-        event: &EventView<'_, CiRenderer>,
-    ) -> std::io::Result<()> {
-        let data = event.data();
-        let msg = &data.message;
-        match data.level {
-            Level::Info => writeln!(f, "      {} {}", "│".dimmed(), msg.dimmed()),
-            Level::Warn => writeln!(f, "      {} {}", "│".yellow(), msg.yellow()),
-            Level::Error => writeln!(f, "      {} {}", "│".red(), msg.red().bold()),
-            Level::Success => writeln!(f, "      {} {}", "│".green(), msg.green()),
+            writeln!(f, "    {} {}", SPINNER[self.tick].magenta(), name.dimmed())
         }
     }
 }
@@ -172,21 +175,21 @@ impl TraceMapper for Mapper {
     type TaskData = TaskData;
 
     // Dispatch on "kind" field to determine which TaskData variant to create.
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> TaskData {
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<TaskData> {
         let mut v = FieldVisitor::default();
         attrs.record(&mut v);
         let name = v
             .name
             .unwrap_or_else(|| attrs.metadata().name().to_string());
-        match v.kind.as_deref() {
+        Some(match v.kind.as_deref() {
             Some("pipeline") => TaskData::Pipeline { name },
             Some("stage") => TaskData::Stage { name },
             _ => TaskData::Step { name },
-        }
+        })
     }
 
     // Maps the "level" string field to a typed Level enum for colored rendering.
-    fn map_event(event: &tracing::Event<'_>) -> EventData {
+    fn map_event(event: &tracing::Event<'_>) -> Option<EventData> {
         let mut v = FieldVisitor::default();
         event.record(&mut v);
         let level = match v.level.as_deref() {
@@ -195,10 +198,10 @@ impl TraceMapper for Mapper {
             Some("success") => Level::Success,
             _ => Level::Info,
         };
-        EventData {
+        Some(EventData {
             level,
             message: v.message.unwrap_or_default(),
-        }
+        })
     }
 }
 
@@ -266,7 +269,8 @@ async fn render_loop(
     mut rx: mpsc::UnboundedReceiver<Action<CiRenderer>>,
     mut shutdown: tokio::sync::oneshot::Receiver<()>,
 ) {
-    let mut renderer = TaskRenderer::new(CiRenderer::default());
+    let mut renderer =
+        TaskRenderer::new(CiRenderer::default()).mode(OutputMode::detect(&std::io::stderr()));
     let mut interval = tokio::time::interval(Duration::from_millis(80));
 
     loop {
@@ -595,6 +599,10 @@ fn print_summary(elapsed: Duration) {
         "✘".red().bold(),
         "⚠".yellow().bold(),
     );
-    eprintln!("    {} {:.1}s elapsed", "⏱".dimmed(), elapsed.as_secs_f64());
+    eprintln!(
+        "    {} {} elapsed",
+        "⏱".dimmed(),
+        HumanDuration::new(elapsed)
+    );
     eprintln!("{}", bar.dimmed());
 }
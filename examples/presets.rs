@@ -0,0 +1,53 @@
+//! Preset renderers with the default mapper — no `Renderer` impl needed.
+//!
+//! Pick a preset with the first argument: `tree` (default), `compact` or `ci`.
+//!
+//! ```sh
+//! cargo run --example presets -- compact
+//! ```
+
+use std::time::Duration;
+
+use trace_tally::presets::{CiRenderer, CompactRenderer, DefaultMapper, TreeRenderer};
+use trace_tally::*;
+use tracing::{info, info_span, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+fn main() {
+    let stderr = std::io::stderr();
+    let registry = tracing_subscriber::registry();
+    match std::env::args().nth(1).as_deref() {
+        Some("compact") => registry
+            .with(DefaultMapper::inline_layer(CompactRenderer::new(), stderr))
+            .init(),
+        Some("ci") => registry
+            .with(DefaultMapper::inline_layer(
+                CiRenderer::new().numbered(true),
+                stderr,
+            ))
+            .init(),
+        _ => registry
+            .with(DefaultMapper::inline_layer(TreeRenderer::new(), stderr))
+            .init(),
+    }
+
+    let deploy = info_span!("deploy", env = "staging");
+    deploy.in_scope(|| {
+        for stage in ["checkout", "build", "upload"] {
+            let span = info_span!("stage", message = stage);
+            let _guard = span.enter();
+            for step in 1..=3 {
+                let span = info_span!("step", n = step);
+                let _guard = span.enter();
+                info!("working on {stage}");
+                std::thread::sleep(Duration::from_millis(150));
+                if step == 2 && stage == "upload" {
+                    warn!(attempt = 2, "retrying slow mirror");
+                }
+                std::thread::sleep(Duration::from_millis(150));
+            }
+        }
+    });
+    info!("deployed");
+}
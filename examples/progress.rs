@@ -127,22 +127,22 @@ impl TraceMapper for Mapper {
     type EventData = Event;
     type TaskData = String;
 
-    fn map_event(event: &tracing::Event<'_>) -> Event {
+    fn map_event(event: &tracing::Event<'_>) -> Option<Event> {
         let mut visitor = FieldVisitor::default();
         event.record(&mut visitor);
-        match (visitor.done, visitor.total) {
+        Some(match (visitor.done, visitor.total) {
             (Some(done), Some(total)) => Event::progress(done, total),
             _ => Event::message(visitor.message.unwrap_or_default()),
-        }
+        })
     }
 
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
         let mut visitor = FieldVisitor::default();
         attrs.record(&mut visitor);
-        match visitor.message {
+        Some(match visitor.message {
             Some(msg) => format!("{}: {msg}", attrs.metadata().name()),
             None => attrs.metadata().name().to_string(),
-        }
+        })
     }
 }
 
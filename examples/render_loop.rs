@@ -33,7 +33,8 @@ impl Renderer for MyRenderer {
     ) -> std::io::Result<()> {
         let indent = " ".repeat(task.depth());
         if !task.active() {
-            return writeln!(f, "{indent}✓ {}", task.data());
+            let check = f.style(Style::new().green().bold(), "✓");
+            return writeln!(f, "{indent}{check} {}", task.data());
         }
         writeln!(f, "{indent}{} {}", self.spinner.frame(), task.data())
     }
@@ -49,6 +50,19 @@ impl Renderer for MyRenderer {
             writeln!(f, "{}  -> {}", " ".repeat(event.depth()), event.data())
         }
     }
+
+    // Name tasks in the end-of-run summary, and print it after the last frame.
+    fn summary_label(&self, task: &TaskView<'_, Self>) -> Option<String> {
+        Some(task.data().clone())
+    }
+
+    fn render_run_summary(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        summary: &RunSummary,
+    ) -> std::io::Result<()> {
+        writeln!(f, "\n{summary}")
+    }
 }
 
 // -- Tracing integration -----------------------------------------------------
@@ -59,17 +73,17 @@ impl TraceMapper for Mapper {
     type EventData = String;
     type TaskData = String;
 
-    fn map_event(event: &tracing::Event<'_>) -> String {
+    fn map_event(event: &tracing::Event<'_>) -> Option<String> {
         let mut message = String::new();
         event.record(&mut MessageVisitor(&mut message));
-        message
+        Some(message)
     }
 
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
         let mut message = String::new();
         attrs.record(&mut MessageVisitor(&mut message));
         let name = attrs.metadata().name();
-        format!("{name}: {message}")
+        Some(format!("{name}: {message}"))
     }
 }
 
@@ -108,7 +122,7 @@ fn main() {
             std::io::stderr(),
         )
         .interval(Duration::from_millis(80))
-        .run_until(rx, || stop_flag.load(Ordering::Relaxed));
+        .run_until(rx, || stop_flag.load(Ordering::Relaxed))
     });
 
     // Simulation: concurrent tasks from multiple threads.
@@ -156,7 +170,10 @@ fn main() {
     t3.join().unwrap();
 
     stop.store(true, Ordering::Relaxed);
-    render_thread.join().unwrap();
+    let summary = render_thread.join().unwrap();
+    if !summary.success() {
+        std::process::exit(1);
+    }
 }
 
 fn sleep(ms: u64) {
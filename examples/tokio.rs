@@ -59,17 +59,17 @@ impl TraceMapper for Mapper {
     type EventData = String;
     type TaskData = String;
 
-    fn map_event(event: &tracing::Event<'_>) -> String {
+    fn map_event(event: &tracing::Event<'_>) -> Option<String> {
         let mut message = String::new();
         event.record(&mut MessageVisitor(&mut message));
-        message
+        Some(message)
     }
 
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
         let mut message = String::new();
         attrs.record(&mut MessageVisitor(&mut message));
         let name = attrs.metadata().name();
-        format!("{name}: {message}")
+        Some(format!("{name}: {message}"))
     }
 }
 
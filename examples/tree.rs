@@ -46,19 +46,19 @@ impl TraceMapper for Mapper {
     type EventData = String;
     type TaskData = String;
 
-    fn map_event(event: &tracing::Event<'_>) -> String {
+    fn map_event(event: &tracing::Event<'_>) -> Option<String> {
         let mut msg = String::new();
         event.record(&mut MessageVisitor(&mut msg));
-        msg
+        Some(msg)
     }
 
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
         let mut msg = String::new();
         attrs.record(&mut MessageVisitor(&mut msg));
-        match msg.is_empty() {
+        Some(match msg.is_empty() {
             true => attrs.metadata().name().to_string(),
             false => msg,
-        }
+        })
     }
 }
 
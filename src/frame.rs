@@ -0,0 +1,327 @@
+use std::io::Write;
+
+use crate::text;
+use crate::{ColorSupport, Glyphs, Style, Styled};
+
+/// Terminal settings a [`FrameWriter`] resolves before each frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FrameOptions {
+    /// Fixed terminal width, or `None` to query the terminal.
+    pub(crate) width: Option<usize>,
+    /// Fixed frame height budget, or `None` to use the terminal height.
+    pub(crate) height: Option<usize>,
+    /// Whether lines wider than the terminal are cut with an ellipsis.
+    pub(crate) truncate: bool,
+    /// Color capability styles are resolved against; `None` means no color.
+    pub(crate) color: Option<ColorSupport>,
+    /// Glyph set widgets draw with; `None` means [`Glyphs::UNICODE`].
+    pub(crate) glyphs: Option<Glyphs>,
+}
+
+impl FrameOptions {
+    /// Fills in any unset size from the terminal, querying it at most once.
+    ///
+    /// The height stays `None` when the terminal size is unknown.
+    pub(crate) fn resolve(mut self) -> Self {
+        if self.width.is_none() || self.height.is_none() {
+            let size = crate::term::terminal_size();
+            self.width = self.width.or(size.map(|(cols, _)| cols));
+            self.height = self
+                .height
+                .or(size.map(|(_, rows)| rows).filter(|&r| r > 0));
+        }
+        self
+    }
+}
+
+/// Write target with ANSI cursor control for frame clearing.
+///
+/// Wraps an [`std::io::Write`] target. Use `write!` / `writeln!` to produce
+/// output within renderer callbacks.
+///
+/// The writer tracks how many terminal rows the frame occupies, including
+/// lines that wrap past [`width`](Self::width), so the next frame can erase
+/// exactly what was drawn.
+///
+/// ```rust,ignore
+/// fn render_task_line(
+///     &mut self, f: &mut FrameWriter<'_>, task: &TaskView<'_, Self>,
+/// ) -> std::io::Result<()> {
+///     writeln!(f, "Task: {}", task.data())
+/// }
+/// ```
+pub struct FrameWriter<'a> {
+    target: &'a mut dyn Write,
+    frame_lines: usize,
+    width: usize,
+    height: Option<usize>,
+    truncate: bool,
+    color: ColorSupport,
+    glyphs: Glyphs,
+    max_rows: Option<usize>,
+    column: usize,
+    scan: Scan,
+    /// Visible text not yet measured, up to the next escape or control byte.
+    text: Vec<u8>,
+    line: Vec<u8>,
+}
+
+/// Escape sequence parser state for row counting across `write` calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scan {
+    Text,
+    Escape,
+    Csi,
+    Osc,
+}
+
+impl<'a> FrameWriter<'a> {
+    pub(crate) fn new(
+        target: &'a mut dyn Write,
+        frame_lines: usize,
+        options: FrameOptions,
+    ) -> Self {
+        Self {
+            target,
+            frame_lines,
+            width: options.width.unwrap_or(crate::term::DEFAULT_WIDTH).max(1),
+            height: options.height,
+            truncate: options.truncate,
+            color: options.color.unwrap_or_default(),
+            glyphs: options.glyphs.unwrap_or(Glyphs::UNICODE),
+            max_rows: None,
+            column: 0,
+            scan: Scan::Text,
+            text: Vec::new(),
+            line: Vec::new(),
+        }
+    }
+
+    /// Width of the terminal in columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Rows available to the frame, if known: the terminal height, or the
+    /// cap set with [`TaskRenderer::max_height`](crate::TaskRenderer::max_height).
+    pub fn height(&self) -> Option<usize> {
+        self.height
+    }
+
+    /// Color capability of the target, after environment detection and overrides.
+    pub fn color_support(&self) -> ColorSupport {
+        self.color
+    }
+
+    /// Glyph set widgets draw with while this writer renders.
+    ///
+    /// See [`Glyphs::current`].
+    pub fn glyphs(&self) -> &Glyphs {
+        &self.glyphs
+    }
+
+    /// Wraps `value` in `style`, resolved against this writer's color support.
+    ///
+    /// ```rust,ignore
+    /// write!(f, "{} ", f.style(Style::new().green().bold(), "✔"))?;
+    /// ```
+    pub fn style<T: std::fmt::Display>(&self, style: Style, value: T) -> Styled<T> {
+        style.paint(value, self.color)
+    }
+
+    /// A writer with the same width and color support that writes to
+    /// `target` without truncating.
+    pub(crate) fn scratch<'b>(&self, target: &'b mut dyn Write) -> FrameWriter<'b> {
+        let options = FrameOptions {
+            width: Some(self.width),
+            height: None,
+            truncate: false,
+            color: Some(self.color),
+            glyphs: Some(self.glyphs),
+        };
+        FrameWriter::new(target, 0, options)
+    }
+
+    pub(crate) fn clear_frame(&mut self) -> Result<(), std::io::Error> {
+        let lines_drawn = self.frame_lines;
+        if lines_drawn > 0 {
+            write!(self.target, "\r\x1b[{}A\x1b[2K\x1b[J", lines_drawn)?;
+            self.target.flush()?;
+        }
+        self.frame_lines = 0;
+        self.column = 0;
+        self.text.clear();
+        Ok(())
+    }
+
+    pub(crate) fn frame_lines(&self) -> usize {
+        self.frame_lines
+    }
+
+    /// Discards any output once the frame reaches `rows` terminal rows.
+    pub(crate) fn clip_rows(&mut self, rows: usize) {
+        self.max_rows = Some(rows);
+    }
+
+    fn clipped(&self) -> bool {
+        self.max_rows.is_some_and(|max| self.frame_lines >= max)
+    }
+
+    /// Writes `buf` to the target and advances the row count.
+    fn emit(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        if self.clipped() {
+            return Ok(());
+        }
+        self.target.write_all(buf)?;
+        self.count_rows(buf);
+        Ok(())
+    }
+
+    /// Emits the buffered line, cut to the terminal width.
+    fn emit_line(&mut self) -> std::io::Result<()> {
+        let line = std::mem::take(&mut self.line);
+        let text = String::from_utf8_lossy(&line);
+        let (body, newline) = match text.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (text.as_ref(), ""),
+        };
+        let cut = text::truncate(body, self.width, self.glyphs.ellipsis);
+        self.emit(cut.as_bytes())?;
+        self.emit(newline.as_bytes())
+    }
+
+    fn count_rows(&mut self, buf: &[u8]) {
+        for &b in buf {
+            if self.scan == Scan::Text && (b >= 0x20 && b != 0x7f) {
+                self.text.push(b);
+                continue;
+            }
+            self.measure_text(true);
+            self.scan = match (self.scan, b) {
+                (Scan::Text, b'\n') => {
+                    self.frame_lines += 1;
+                    self.column = 0;
+                    Scan::Text
+                }
+                (Scan::Text, b'\r') => {
+                    self.column = 0;
+                    Scan::Text
+                }
+                (Scan::Text, 0x1b) => Scan::Escape,
+                (Scan::Text, _) => Scan::Text,
+                (Scan::Escape, b'[') => Scan::Csi,
+                (Scan::Escape, b']') => Scan::Osc,
+                (Scan::Escape, _) => Scan::Text,
+                (Scan::Csi, 0x40..=0x7e) => Scan::Text,
+                (Scan::Csi, _) => Scan::Csi,
+                (Scan::Osc, 0x07) => Scan::Text,
+                (Scan::Osc, 0x1b) => Scan::Escape,
+                (Scan::Osc, _) => Scan::Osc,
+            };
+        }
+        self.measure_text(false);
+    }
+
+    /// Advances the cursor column past the pending visible text, wrapping
+    /// onto new rows as the terminal would.
+    ///
+    /// Unless `all`, a char split across `write` calls is kept for the next.
+    fn measure_text(&mut self, all: bool) {
+        if self.text.is_empty() {
+            return;
+        }
+        let complete = match std::str::from_utf8(&self.text) {
+            Ok(s) => s.len(),
+            Err(e) if !all && e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.text.len(),
+        };
+        let text: Vec<u8> = self.text.drain(..complete).collect();
+        for segment in text::segments(&String::from_utf8_lossy(&text)) {
+            if let text::Segment::Grapheme(g) = segment {
+                let w = text::grapheme_width(g);
+                self.frame_lines += text::wrap(&mut self.column, w, self.width);
+            }
+        }
+    }
+}
+
+impl<'a> Write for FrameWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !self.truncate {
+            if self.clipped() {
+                return Ok(buf.len());
+            }
+            let n = self.target.write(buf)?;
+            self.count_rows(&buf[..n]);
+            return Ok(n);
+        }
+        for chunk in buf.split_inclusive(|&b| b == b'\n') {
+            self.line.extend_from_slice(chunk);
+            if chunk.ends_with(b"\n") {
+                self.emit_line()?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.line.is_empty() {
+            self.emit_line()?;
+        }
+        self.target.flush()
+    }
+}
+
+/// Appends to `out` the cursor movements and rewrites that turn the frame
+/// `prev` into `next` on screen, touching only lines that changed.
+///
+/// Expects the cursor at the start of the row just below `prev`, and leaves
+/// it just below `next`. Lines that change height force everything after
+/// them to be rewritten.
+pub(crate) fn redraw(out: &mut Vec<u8>, prev: &[String], next: &[String], width: usize) {
+    let rows = |line: &String| text::rows(line, width);
+    let len = prev.len().max(next.len());
+    let Some(first) = (0..len).find(|&i| prev.get(i) != next.get(i)) else {
+        return;
+    };
+
+    let up = prev[first.min(prev.len())..]
+        .iter()
+        .map(rows)
+        .sum::<usize>();
+    if up > 0 {
+        let _ = write!(out, "\r\x1b[{up}A");
+    }
+
+    let mut skip = 0;
+    let mut tail = false;
+    for (i, line) in next.iter().enumerate().skip(first) {
+        let old = prev.get(i);
+        if !tail && old == Some(line) {
+            skip += rows(line);
+            continue;
+        }
+        if skip > 0 {
+            let _ = write!(out, "\x1b[{skip}B");
+            skip = 0;
+        }
+        match old {
+            Some(old) if !tail && rows(old) == 1 && rows(line) == 1 => {
+                let _ = writeln!(out, "\x1b[2K{line}");
+            }
+            _ => {
+                if !tail && old.is_some() {
+                    out.extend_from_slice(b"\x1b[J");
+                }
+                tail = true;
+                let _ = writeln!(out, "{line}");
+            }
+        }
+    }
+    if skip > 0 {
+        let _ = write!(out, "\x1b[{skip}B");
+    }
+    if !tail && next.len() < prev.len() {
+        out.extend_from_slice(b"\x1b[J");
+    }
+}
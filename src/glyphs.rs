@@ -0,0 +1,153 @@
+use std::cell::Cell;
+use std::sync::OnceLock;
+
+/// The characters built-in widgets and presets draw with.
+///
+/// Box-drawing, braille and check-mark characters show up as mojibake on
+/// serial consoles and some CI log viewers. [`Glyphs::detect`] picks
+/// [`ASCII`](Self::ASCII) for those from the locale and `TERM`, and
+/// [`TaskRenderer::glyphs`](crate::TaskRenderer::glyphs) overrides the choice.
+///
+/// Widgets such as [`TreeIndent`](crate::TreeIndent) read the set of the
+/// [`FrameWriter`](crate::FrameWriter) currently rendering through
+/// [`Glyphs::current`], so they follow the override without extra arguments.
+///
+/// A custom set starts from one of the built-in ones:
+///
+/// ```rust
+/// use trace_tally::Glyphs;
+///
+/// let glyphs = Glyphs {
+///     spinner: &["◐", "◓", "◑", "◒"],
+///     ..Glyphs::UNICODE
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glyphs {
+    /// Tree connector for a task with siblings below it: `├── `.
+    pub branch: &'static str,
+    /// Tree connector for the last sibling: `└── `.
+    pub last_branch: &'static str,
+    /// Tree continuation past a level with more siblings: `│   `.
+    pub pipe: &'static str,
+    /// Tree continuation past a finished level, as wide as the others.
+    pub space: &'static str,
+    /// Vertical rail beside event lines: `│`.
+    pub rail: &'static str,
+    /// Frames of the default [`Spinner`](crate::Spinner).
+    pub spinner: &'static [&'static str],
+    /// Filled part of a [`ProgressBar`](crate::ProgressBar).
+    pub bar_filled: char,
+    /// Empty part of a [`ProgressBar`](crate::ProgressBar).
+    pub bar_empty: char,
+    /// Partly filled cells of a [`ProgressBar`](crate::ProgressBar), from
+    /// the smallest step up: `▏` to `▉`. Empty to fill whole cells only.
+    pub bar_partial: &'static [char],
+    /// Levels of a [`Sparkline`](crate::Sparkline), lowest first: `▁` to `█`.
+    pub sparkline: &'static [char],
+    /// Marks a completed task: `✔`.
+    pub done: &'static str,
+    /// Marks a cancelled task: `✖`.
+    pub cancelled: &'static str,
+    /// Separates path segments: ` › `.
+    pub separator: &'static str,
+    /// Ends text cut to fit the terminal width: `…`.
+    pub ellipsis: &'static str,
+}
+
+impl Glyphs {
+    /// Box-drawing and symbol characters, for UTF-8 terminals.
+    pub const UNICODE: Self = Self {
+        branch: "├── ",
+        last_branch: "└── ",
+        pipe: "│   ",
+        space: "    ",
+        rail: "│",
+        spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+        bar_filled: '█',
+        bar_empty: '░',
+        bar_partial: &['▏', '▎', '▍', '▌', '▋', '▊', '▉'],
+        sparkline: &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        done: "✔",
+        cancelled: "✖",
+        separator: " › ",
+        ellipsis: "…",
+    };
+
+    /// Plain ASCII, for serial consoles and non-UTF-8 locales.
+    pub const ASCII: Self = Self {
+        branch: "|-- ",
+        last_branch: "`-- ",
+        pipe: "|   ",
+        space: "    ",
+        rail: "|",
+        spinner: &["|", "/", "-", "\\"],
+        bar_filled: '#',
+        bar_empty: '-',
+        bar_partial: &[],
+        sparkline: &['_', '.', '-', '~', '=', '+', '*', '#'],
+        done: "+",
+        cancelled: "x",
+        separator: " > ",
+        ellipsis: "~",
+    };
+
+    /// Picks a set for stderr from the environment.
+    ///
+    /// - `TERM=dumb`, `TERM=linux` (the kernel console) and `vt*` terminals
+    ///   get [`ASCII`](Self::ASCII).
+    /// - So does a locale (`LC_ALL`, then `LC_CTYPE`, then `LANG`) that is
+    ///   set but isn't UTF-8, such as `C` or `POSIX`.
+    /// - Everything else gets [`UNICODE`](Self::UNICODE).
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let term = var("TERM").unwrap_or_default();
+        if term == "dumb" || term == "linux" || term.starts_with("vt") {
+            return Self::ASCII;
+        }
+        let locale = var("LC_ALL")
+            .or_else(|| var("LC_CTYPE"))
+            .or_else(|| var("LANG"));
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_ascii_lowercase();
+                if locale.contains("utf-8") || locale.contains("utf8") {
+                    Self::UNICODE
+                } else {
+                    Self::ASCII
+                }
+            }
+            None => Self::UNICODE,
+        }
+    }
+
+    /// The set widgets should draw with right now.
+    ///
+    /// Inside a render this is the rendering [`FrameWriter`](crate::FrameWriter)'s
+    /// set; elsewhere it is [`detect`](Self::detect)ed once per process.
+    pub fn current() -> Self {
+        static DETECTED: OnceLock<Glyphs> = OnceLock::new();
+        CURRENT
+            .get()
+            .unwrap_or_else(|| *DETECTED.get_or_init(Self::detect))
+    }
+}
+
+/// Makes a set [`Glyphs::current`] on this thread until dropped.
+pub(crate) struct GlyphScope(Option<Glyphs>);
+
+impl GlyphScope {
+    pub(crate) fn enter(glyphs: Glyphs) -> Self {
+        Self(CURRENT.replace(Some(glyphs)))
+    }
+}
+
+impl Drop for GlyphScope {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<Glyphs>> = const { Cell::new(None) };
+}
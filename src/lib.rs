@@ -1,12 +1,24 @@
 #![cfg_attr(feature = "tracing", doc = include_str!("../README.md"))]
 
+pub(crate) mod collapse;
+pub(crate) mod frame;
+pub(crate) mod glyphs;
+pub(crate) mod line;
+pub(crate) mod offscreen;
+#[cfg(feature = "ratatui")]
+pub(crate) mod ratatui;
 pub(crate) mod runner;
+pub(crate) mod style;
+pub(crate) mod summary;
 pub(crate) mod task;
+pub(crate) mod term;
+pub(crate) mod text;
 #[cfg(feature = "tracing")]
 pub(crate) mod tracing;
 pub(crate) mod view;
 pub(crate) mod writer;
 
+pub mod presets;
 pub mod widgets;
 
 #[cfg(test)]
@@ -14,13 +26,22 @@ mod test;
 
 /// Re-exports of all public types and traits.
 pub mod prelude {
-    pub use crate::runner::{ActionSource, RenderLoop};
+    pub use crate::collapse::{Collapse, TaskSummary};
+    pub use crate::frame::FrameWriter;
+    pub use crate::glyphs::Glyphs;
+    pub use crate::line::LineBuilder;
+    pub use crate::offscreen::{Line, StyleSpan};
+    #[cfg(feature = "ratatui")]
+    pub use crate::ratatui::{TaskTreeState, TaskTreeWidget};
+    pub use crate::runner::{ActionSource, RenderHandle, RenderLoop};
+    pub use crate::style::{Color, ColorSupport, Style, Styled};
+    pub use crate::summary::RunSummary;
     pub use crate::task::TaskId;
     #[cfg(feature = "tracing")]
     pub use crate::tracing::*;
-    pub use crate::view::{EventView, FrameWriter, TaskView};
+    pub use crate::view::{EventView, TaskView};
     pub use crate::widgets::*;
-    pub use crate::writer::TaskRenderer;
+    pub use crate::writer::{OutputMode, TaskRenderer};
     pub use crate::{Action, Renderer};
 }
 
@@ -75,22 +96,40 @@ pub trait Renderer: Sized {
     /// Called once at the end of each render frame, after all tasks have been visited.
     fn on_render_end(&mut self) {}
 
+    /// Called before a frame when the terminal size differs from the last
+    /// frame's, and before the first frame.
+    ///
+    /// `width` is in columns; `height` is the rows available to the frame,
+    /// if known. Both are also available from the [`FrameWriter`].
+    fn on_resize(&mut self, width: usize, height: Option<usize>) {
+        let _ = (width, height);
+    }
+
     /// Renders a complete task and its descendants.
     ///
-    /// The default implementation renders the task line, then the last 3
-    /// buffered events (skipped for completed tasks), then recurses into
-    /// subtasks. Override this to change traversal order or the event cap.
+    /// The default implementation renders the task line, then the last
+    /// [`max_events`](Renderer::max_events) buffered events (skipped for
+    /// completed tasks), then recurses into subtasks. A task
+    /// [collapsed](Renderer::collapse) by its policy renders only
+    /// [`render_summary_line`](Renderer::render_summary_line). Override this
+    /// to change traversal order.
     #[allow(unused_variables)]
     fn render_task(
         &mut self,
         f: &mut FrameWriter<'_>,
         task: &TaskView<'_, Self>,
     ) -> Result<(), std::io::Error> {
+        if collapse::is_collapsed(self, task) {
+            return self.render_summary_line(f, task, &task.summary());
+        }
         self.render_task_line(f, task)?;
         if task.active() {
-            for event in task.events().rev().take(3).rev() {
+            for event in task.events().rev().take(self.max_events()).rev() {
                 self.render_event_line(f, &event)?;
             }
+            if task.suppressed_events() > 0 {
+                self.render_suppressed_line(f, task)?;
+            }
         }
         for subtask in task.subtasks() {
             self.render_task(f, &subtask)?;
@@ -98,6 +137,132 @@ pub trait Renderer: Sized {
         Ok(())
     }
 
+    /// Estimated number of lines this task takes in the frame, excluding
+    /// its subtasks.
+    ///
+    /// Used to decide which tasks fit when the frame is clipped to the
+    /// terminal height. The default matches [`render_task`]: one task line
+    /// plus up to [`max_events`](Renderer::max_events) events and the
+    /// suppressed-events marker while active, and nothing for tasks folded
+    /// into a collapsed ancestor. Override it alongside [`render_task`] if you
+    /// draw more or fewer lines per task.
+    ///
+    /// [`render_task`]: Renderer::render_task
+    fn task_height(&self, task: &TaskView<'_, Self>) -> usize {
+        if collapse::inside_collapsed(self, task) {
+            return 0;
+        }
+        match task.active() {
+            true => {
+                let marker = usize::from(task.suppressed_events() > 0);
+                1 + task.events().len().min(self.max_events()) + marker
+            }
+            false => 1,
+        }
+    }
+
+    /// Number of most recent events the default [`render_task`] shows under
+    /// an active task. Defaults to 3.
+    ///
+    /// [`render_task`]: Renderer::render_task
+    fn max_events(&self) -> usize {
+        3
+    }
+
+    /// When `task`'s subtree is folded into a single summary line once it
+    /// finishes. Defaults to [`Collapse::Never`].
+    #[allow(unused_variables)]
+    fn collapse(&self, task: &TaskView<'_, Self>) -> Collapse {
+        Collapse::Never
+    }
+
+    /// Renders the line that replaces a collapsed task and its subtree.
+    ///
+    /// The default renders the task line followed by `summary`, e.g.
+    /// `✔ tests (118 done, 2 cancelled) 4.1s`.
+    fn render_summary_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+        summary: &TaskSummary,
+    ) -> Result<(), std::io::Error> {
+        use std::io::Write;
+        let mut line = Vec::new();
+        self.render_task_line(&mut f.scratch(&mut line), task)?;
+        let line = String::from_utf8_lossy(&line);
+        match line.trim_end_matches('\n') {
+            "" => Ok(()),
+            line => writeln!(f, "{line} {summary}"),
+        }
+    }
+
+    /// Label for `task` in the [`RunSummary`] list of slowest tasks, or
+    /// `None` (the default) to leave it out.
+    #[allow(unused_variables)]
+    fn summary_label(&self, task: &TaskView<'_, Self>) -> Option<String> {
+        None
+    }
+
+    /// The message to list in the [`RunSummary`] if `event` reports an error,
+    /// or `None` (the default) otherwise.
+    ///
+    /// A task that receives an error event counts as failed when it ends.
+    #[allow(unused_variables)]
+    fn error_message(&self, event: &EventView<'_, Self>) -> Option<String> {
+        None
+    }
+
+    /// Prints the end-of-run recap after the final frame of a
+    /// [`RenderLoop`]. Prints nothing by default.
+    ///
+    /// ```rust,ignore
+    /// fn render_run_summary(
+    ///     &mut self, f: &mut FrameWriter<'_>, summary: &RunSummary,
+    /// ) -> std::io::Result<()> {
+    ///     writeln!(f, "{summary}")
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn render_run_summary(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        summary: &RunSummary,
+    ) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+
+    /// Renders the line shown in place of tasks that didn't fit in the frame.
+    fn render_overflow_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        hidden: usize,
+    ) -> Result<(), std::io::Error> {
+        use std::io::Write;
+        let ellipsis = f.glyphs().ellipsis;
+        match hidden {
+            1 => writeln!(f, "{ellipsis} and 1 more task"),
+            n => writeln!(f, "{ellipsis} and {n} more tasks"),
+        }
+    }
+
+    /// Renders the marker for events a rate limit dropped from `task`, as
+    /// counted by [`TaskView::suppressed_events`].
+    ///
+    /// Drawn below an active task's events, and once into the scrollback for
+    /// events dropped outside any task (where `task` is the root).
+    fn render_suppressed_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> Result<(), std::io::Error> {
+        use std::io::Write;
+        let ellipsis = f.glyphs().ellipsis;
+        match task.suppressed_events() {
+            1 => writeln!(f, "{ellipsis} suppressed 1 event"),
+            n => writeln!(f, "{ellipsis} suppressed {n} events"),
+        }
+    }
+
     /// Renders the task header on task start.
     #[allow(unused_variables)]
     fn render_task_line(
@@ -158,6 +323,14 @@ pub enum Action<R: Renderer> {
     },
     /// A task has completed.
     TaskEnd { id: TaskId },
+    /// `count` events on a task (or the root if `parent` is `None`) were
+    /// dropped by a rate limit before reaching the renderer.
+    ///
+    /// Accumulated per task and exposed via [`TaskView::suppressed_events`].
+    EventsSuppressed {
+        parent: Option<TaskId>,
+        count: usize,
+    },
     /// Mark all pending tasks as cancelled.
     ///
     /// Walks every task reachable from root and sets them as cancelled.
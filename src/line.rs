@@ -0,0 +1,210 @@
+use std::fmt::Display;
+use std::io::Write;
+
+use crate::FrameWriter;
+use crate::text;
+
+/// Lays out one line in columns across the terminal width.
+///
+/// Created by [`FrameWriter::line`]. Columns are added to a left, center
+/// or right group; [`fill`](Self::fill) adds a flexible column that takes
+/// up the remaining space and is truncated first when the line is too wide.
+/// Widths are measured on visible text, so styled values line up too.
+///
+/// ```rust,ignore
+/// f.line()
+///     .left(TreeIndent::of(task))
+///     .fill(task.data())
+///     .right(f.style(Style::new().dim(), elapsed))
+///     .finish()?;
+/// // => ├── compiling trace-tally                      1.2s
+/// ```
+pub struct LineBuilder<'w, 'a> {
+    f: &'w mut FrameWriter<'a>,
+    columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug)]
+struct Column {
+    text: String,
+    group: Group,
+    flex: bool,
+    min: usize,
+    max: Option<usize>,
+    /// Columns the text is fitted to.
+    width: usize,
+}
+
+impl<'w, 'a> LineBuilder<'w, 'a> {
+    pub(crate) fn new(f: &'w mut FrameWriter<'a>) -> Self {
+        Self {
+            f,
+            columns: Vec::new(),
+        }
+    }
+
+    fn push(mut self, group: Group, flex: bool, value: impl Display) -> Self {
+        self.columns.push(Column {
+            text: value.to_string(),
+            group,
+            flex,
+            min: 0,
+            max: None,
+            width: 0,
+        });
+        self
+    }
+
+    /// Adds a column aligned to the left edge.
+    pub fn left(self, value: impl Display) -> Self {
+        self.push(Group::Left, false, value)
+    }
+
+    /// Adds a column centered on the line.
+    pub fn center(self, value: impl Display) -> Self {
+        self.push(Group::Center, false, value)
+    }
+
+    /// Adds a column aligned to the right edge.
+    pub fn right(self, value: impl Display) -> Self {
+        self.push(Group::Right, false, value)
+    }
+
+    /// Adds a flexible column after the left columns.
+    ///
+    /// It is padded to push the center and right columns to their place,
+    /// and cut with an [ellipsis](crate::Glyphs::ellipsis) when the line
+    /// doesn't fit.
+    pub fn fill(self, value: impl Display) -> Self {
+        self.push(Group::Left, true, value)
+    }
+
+    /// Pads the last added column to at least `width` columns. A flexible
+    /// column isn't cut below this.
+    pub fn min_width(mut self, width: usize) -> Self {
+        if let Some(column) = self.columns.last_mut() {
+            column.min = width;
+        }
+        self
+    }
+
+    /// Cuts the last added column to at most `width` columns. A flexible
+    /// column doesn't grow past this.
+    pub fn max_width(mut self, width: usize) -> Self {
+        if let Some(column) = self.columns.last_mut() {
+            column.max = Some(width);
+        }
+        self
+    }
+
+    /// Writes the line, followed by a newline.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        let line = layout(&mut self.columns, self.f.width(), self.f.glyphs().ellipsis);
+        writeln!(self.f, "{line}")
+    }
+}
+
+impl<'a> FrameWriter<'a> {
+    /// Starts a [`LineBuilder`] for a line laid out in columns.
+    pub fn line(&mut self) -> LineBuilder<'_, 'a> {
+        LineBuilder::new(self)
+    }
+}
+
+/// Sizes `columns` to fit `width` and joins them into one line.
+fn layout(columns: &mut [Column], width: usize, ellipsis: &str) -> String {
+    for column in columns.iter_mut() {
+        let natural = text::display_width(&column.text);
+        let capped = column.max.map_or(natural, |max| natural.min(max));
+        column.width = capped.max(column.min);
+    }
+    let total = |columns: &[Column]| columns.iter().map(|c| c.width).sum::<usize>();
+
+    // Shrink flexible columns when the line is too wide...
+    let mut excess = total(columns).saturating_sub(width);
+    for column in columns.iter_mut().filter(|c| c.flex) {
+        let cut = excess.min(column.width.saturating_sub(column.min));
+        column.width -= cut;
+        excess -= cut;
+    }
+
+    // ...or grow them to push the other groups outwards.
+    let aligned = columns.iter().any(|c| c.group != Group::Left);
+    if aligned {
+        let mut slack = width.saturating_sub(total(columns));
+        for column in columns.iter_mut().filter(|c| c.flex) {
+            let room = column
+                .max
+                .map_or(slack, |max| max.saturating_sub(column.width));
+            let grow = slack.min(room);
+            column.width += grow;
+            slack -= grow;
+        }
+    }
+
+    let group_width = |group| {
+        columns
+            .iter()
+            .filter(|c| c.group == group)
+            .map(|c| c.width)
+            .sum::<usize>()
+    };
+    let (left, center, right) = (
+        group_width(Group::Left),
+        group_width(Group::Center),
+        group_width(Group::Right),
+    );
+    let slack = width.saturating_sub(left + center + right);
+    let (before_center, before_right) = match (center, right) {
+        (0, 0) => (0, 0),
+        (0, _) => (0, slack),
+        (_, 0) => (
+            (width.saturating_sub(center) / 2)
+                .saturating_sub(left)
+                .min(slack),
+            0,
+        ),
+        _ => {
+            let before = (width.saturating_sub(center) / 2)
+                .saturating_sub(left)
+                .min(slack);
+            (before, slack - before)
+        }
+    };
+
+    let mut out = String::new();
+    for group in [Group::Left, Group::Center, Group::Right] {
+        match group {
+            Group::Center => out.push_str(&" ".repeat(before_center)),
+            Group::Right => out.push_str(&" ".repeat(before_right)),
+            Group::Left => {}
+        }
+        for column in columns.iter().filter(|c| c.group == group) {
+            fit(&mut out, column, ellipsis);
+        }
+    }
+    out
+}
+
+/// Appends `column`'s text cut or padded to exactly its width.
+fn fit(out: &mut String, column: &Column, ellipsis: &str) {
+    let cut = text::truncate(&column.text, column.width, ellipsis);
+    let pad = " ".repeat(column.width.saturating_sub(text::display_width(&cut)));
+    match column.group {
+        Group::Right => {
+            out.push_str(&pad);
+            out.push_str(&cut);
+        }
+        _ => {
+            out.push_str(&cut);
+            out.push_str(&pad);
+        }
+    }
+}
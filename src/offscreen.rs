@@ -0,0 +1,94 @@
+use std::ops::Range;
+
+use crate::Style;
+use crate::text::{self, Segment};
+
+/// One rendered line of plain text with the styles applied to it.
+///
+/// Produced by [`TaskRenderer::render_lines`](crate::TaskRenderer::render_lines)
+/// for drawing the task tree inside another UI. The text holds no escape
+/// sequences; colors and attributes are in [`spans`](Self::spans).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Line {
+    /// The visible text.
+    pub text: String,
+    /// Styled byte ranges of [`text`](Self::text), in order and not
+    /// overlapping. Unstyled text isn't covered.
+    pub spans: Vec<StyleSpan>,
+}
+
+/// A byte range of a [`Line`]'s text drawn with one [`Style`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleSpan {
+    pub range: Range<usize>,
+    pub style: Style,
+}
+
+impl Line {
+    /// Splits the line into consecutive runs of text and their style,
+    /// covering the whole text. Unstyled runs get [`Style::new`].
+    pub fn styled(&self) -> impl Iterator<Item = (&str, Style)> {
+        let mut runs = Vec::new();
+        let mut at = 0;
+        for span in &self.spans {
+            if span.range.start > at {
+                runs.push((&self.text[at..span.range.start], Style::new()));
+            }
+            runs.push((&self.text[span.range.clone()], span.style));
+            at = span.range.end;
+        }
+        if at < self.text.len() {
+            runs.push((&self.text[at..], Style::new()));
+        }
+        runs.into_iter()
+    }
+
+    /// Parses one line of terminal output, turning SGR escapes into spans
+    /// and dropping any other escape sequence.
+    fn parse(line: &str) -> Self {
+        let mut out = Line::default();
+        let mut style = Style::new();
+        let mut start = 0;
+        for segment in text::segments(line) {
+            match segment {
+                Segment::Escape(escape) => {
+                    let Some(params) = escape
+                        .strip_prefix("\x1b[")
+                        .and_then(|e| e.strip_suffix('m'))
+                    else {
+                        continue;
+                    };
+                    let mut next = style;
+                    next.apply_sgr(params);
+                    if next != style {
+                        out.close_span(start, style);
+                        start = out.text.len();
+                        style = next;
+                    }
+                }
+                Segment::Grapheme(g) if g.starts_with(char::is_control) => {}
+                Segment::Grapheme(g) => out.text.push_str(g),
+            }
+        }
+        out.close_span(start, style);
+        out
+    }
+
+    fn close_span(&mut self, start: usize, style: Style) {
+        if style != Style::new() && start < self.text.len() {
+            self.spans.push(StyleSpan {
+                range: start..self.text.len(),
+                style,
+            });
+        }
+    }
+}
+
+/// Splits rendered output into [`Line`]s.
+pub(crate) fn lines(output: &str) -> Vec<Line> {
+    let output = output.strip_suffix('\n').unwrap_or(output);
+    if output.is_empty() {
+        return Vec::new();
+    }
+    output.split('\n').map(Line::parse).collect()
+}
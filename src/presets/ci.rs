@@ -0,0 +1,177 @@
+use std::io::Write;
+
+use super::{EventInfo, TaskInfo, status, suppressed};
+use crate::widgets::Spinner;
+use crate::{Collapse, EventView, FrameWriter, Renderer, Style, TaskSummary, TaskView};
+
+/// Draws nested stages as indented steps, in the style of a CI log.
+///
+/// Finished tasks below the root collapse into a summary line, hiding their
+/// subtasks and events.
+///
+/// ```text
+/// ⠹ pipeline
+///   ✔ [1/3] checkout (3 done) 1.2s
+///   ⠹ [2/3] build
+///     ⠹ cargo build --release
+///       │ Compiling serde v1.0.200
+/// ```
+///
+/// ```rust,ignore
+/// let renderer = CiRenderer::new().numbered(true).events(5);
+/// ```
+pub struct CiRenderer {
+    spinner: Spinner,
+    events: usize,
+    collapse: Collapse,
+    numbered: bool,
+}
+
+impl CiRenderer {
+    pub fn new() -> Self {
+        Self {
+            spinner: Spinner::dots(),
+            events: 3,
+            collapse: Collapse::Always,
+            numbered: false,
+        }
+    }
+
+    /// Spinner drawn in front of active tasks.
+    pub fn spinner(mut self, spinner: Spinner) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
+    /// Number of most recent events shown under each active task (default 3).
+    pub fn events(mut self, n: usize) -> Self {
+        self.events = n;
+        self
+    }
+
+    /// When finished tasks below the root fold into a summary line
+    /// (default [`Collapse::Always`]).
+    pub fn collapse(mut self, collapse: Collapse) -> Self {
+        self.collapse = collapse;
+        self
+    }
+
+    /// Whether tasks below the root are labelled with their position among
+    /// their siblings, e.g. `[2/7]` (default `false`).
+    ///
+    /// The total counts the siblings started so far.
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.numbered = numbered;
+        self
+    }
+
+    fn write_name(
+        &self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let status = status(f, task, &self.spinner, Style::new().magenta());
+        let name = &task.data().name;
+        if task.depth() <= 1 {
+            return write!(f, "{status} {}", f.style(Style::new().bold(), name));
+        }
+        write!(f, "{}{status} ", "  ".repeat(task.depth() - 1))?;
+        if self.numbered
+            && let Some(parent) = task.parent()
+        {
+            let label = format!("[{}/{}]", task.index() + 1, parent.subtasks().len());
+            write!(f, "{} ", f.style(Style::new().dim(), label))?;
+        }
+        let style = match (task.active(), task.depth()) {
+            (false, _) => Style::new(),
+            (true, 2) => Style::new().cyan(),
+            (true, _) => Style::new().dim(),
+        };
+        write!(f, "{}", f.style(style, name))
+    }
+}
+
+impl Default for CiRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for CiRenderer {
+    type EventData = EventInfo;
+    type TaskData = TaskInfo;
+
+    fn on_render_start(&mut self) {
+        self.spinner.tick();
+    }
+
+    fn max_events(&self) -> usize {
+        self.events
+    }
+
+    fn collapse(&self, task: &TaskView<'_, Self>) -> Collapse {
+        match task.depth() {
+            1 => Collapse::Never,
+            _ => self.collapse,
+        }
+    }
+
+    fn render_summary_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+        summary: &TaskSummary,
+    ) -> std::io::Result<()> {
+        self.write_name(f, task)?;
+        writeln!(f, " {}", f.style(Style::new().dim(), summary))
+    }
+
+    fn summary_label(&self, task: &TaskView<'_, Self>) -> Option<String> {
+        Some(task.data().name.clone())
+    }
+
+    fn error_message(&self, event: &EventView<'_, Self>) -> Option<String> {
+        super::error_message(event.data())
+    }
+
+    fn render_task_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        self.write_name(f, task)?;
+        writeln!(f)
+    }
+
+    fn render_event_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        event: &EventView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let data = event.data();
+        let style = match data.level {
+            super::Level::Info => Style::new().dim(),
+            level => level.style(),
+        };
+        let message = f.style(style, &data.message);
+        if event.is_root() {
+            return writeln!(f, "{message}");
+        }
+        let indent = "  ".repeat(event.depth());
+        writeln!(f, "{indent}{} {message}", f.style(style, f.glyphs().rail))
+    }
+
+    fn render_suppressed_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let marker = suppressed(f, task);
+        if task.id().is_root() {
+            return writeln!(f, "{}", f.style(Style::new().dim(), marker));
+        }
+        let indent = "  ".repeat(task.depth());
+        let line = format!("{} {marker}", f.glyphs().rail);
+        writeln!(f, "{indent}{}", f.style(Style::new().dim(), line))
+    }
+}
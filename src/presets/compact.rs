@@ -0,0 +1,196 @@
+use std::io::Write;
+
+use super::{EventInfo, TaskInfo, status};
+use crate::widgets::{Elapsed, Spinner};
+use crate::{EventView, FrameWriter, Renderer, Style, TaskView};
+
+/// Draws a single line per root task.
+///
+/// Each line shows the path down to the innermost active subtask and the
+/// latest event along it:
+///
+/// ```text
+/// ⠹ deploy › upload › eu-west-1  12/40 files  4.2s
+/// ✔ build
+/// ```
+///
+/// ```rust,ignore
+/// let renderer = CompactRenderer::new().separator(" / ");
+/// ```
+pub struct CompactRenderer {
+    spinner: Spinner,
+    /// `None` uses the glyph set's separator.
+    separator: Option<&'static str>,
+    events: bool,
+    elapsed: bool,
+}
+
+impl CompactRenderer {
+    pub fn new() -> Self {
+        Self {
+            spinner: Spinner::dots(),
+            separator: None,
+            events: true,
+            elapsed: true,
+        }
+    }
+
+    /// Spinner drawn in front of active tasks.
+    pub fn spinner(mut self, spinner: Spinner) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
+    /// Text placed between task names in the path (default `" › "`, or
+    /// `" > "` with [ASCII glyphs](crate::Glyphs::ASCII)).
+    pub fn separator(mut self, separator: &'static str) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Whether the latest event is shown after the path (default `true`).
+    pub fn events(mut self, show: bool) -> Self {
+        self.events = show;
+        self
+    }
+
+    /// Whether active tasks show their elapsed time (default `true`).
+    pub fn elapsed(mut self, show: bool) -> Self {
+        self.elapsed = show;
+        self
+    }
+
+    /// Writes `status`, the names from `task`'s root down to `task`, then
+    /// `event` and the elapsed time of `timed`.
+    fn write_line(
+        &self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+        timed: &TaskView<'_, Self>,
+        event: Option<&EventInfo>,
+    ) -> std::io::Result<()> {
+        let status = status(f, timed, &self.spinner, Style::new().cyan());
+        write!(f, "{status} ")?;
+        for (i, task) in path(task).iter().rev().enumerate() {
+            if i > 0 {
+                let separator = self.separator.unwrap_or(f.glyphs().separator);
+                write!(f, "{}", f.style(Style::new().dim(), separator))?;
+            }
+            match i {
+                0 => write!(f, "{}", f.style(Style::new().bold(), &task.data().name))?,
+                _ => write!(f, "{}", task.data().name)?,
+            }
+        }
+        if let Some(event) = event.filter(|_| self.events) {
+            write!(
+                f,
+                "  {}",
+                f.style(event.level.style().dim(), &event.message)
+            )?;
+        }
+        if self.elapsed && timed.active() {
+            write!(f, "  {}", f.style(Style::new().dim(), Elapsed::of(timed)))?;
+        }
+        writeln!(f)
+    }
+}
+
+impl Default for CompactRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `task` and its ancestors, innermost first, excluding the virtual root.
+fn path<'a>(task: &TaskView<'a, CompactRenderer>) -> Vec<TaskView<'a, CompactRenderer>> {
+    let mut path = vec![task.view(task.id())];
+    loop {
+        let next = match path.last().unwrap().parent() {
+            Some(parent) if parent.depth() > 0 => parent.id(),
+            _ => break,
+        };
+        path.push(task.view(next));
+    }
+    path
+}
+
+impl Renderer for CompactRenderer {
+    type EventData = EventInfo;
+    type TaskData = TaskInfo;
+
+    fn on_render_start(&mut self) {
+        self.spinner.tick();
+    }
+
+    fn render_task(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        if !task.active() {
+            return self.write_line(f, task, task, None);
+        }
+        // Follow the most recently started active child down the tree,
+        // remembering the latest event seen on the way.
+        let mut leaf = task.view(task.id());
+        let mut event = None;
+        loop {
+            if let Some(last) = leaf.events().last() {
+                event = Some(last.data().clone());
+            }
+            let next = leaf.subtasks().rev().find(|t| t.active()).map(|t| t.id());
+            match next {
+                Some(next) => leaf = task.view(next),
+                None => break,
+            }
+        }
+        self.write_line(f, &leaf, task, event.as_ref())
+    }
+
+    fn task_height(&self, task: &TaskView<'_, Self>) -> usize {
+        // Subtasks share their root's line.
+        match task.depth() {
+            1 => 1,
+            _ => 0,
+        }
+    }
+
+    fn summary_label(&self, task: &TaskView<'_, Self>) -> Option<String> {
+        Some(task.data().name.clone())
+    }
+
+    fn error_message(&self, event: &EventView<'_, Self>) -> Option<String> {
+        super::error_message(event.data())
+    }
+
+    fn render_task_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        self.write_line(f, task, task, None)
+    }
+
+    fn render_event_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        event: &EventView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let data = event.data();
+        let message = f.style(data.level.style(), &data.message);
+        match event.is_root() {
+            true => writeln!(f, "{message}"),
+            false => {
+                let task = event.task();
+                let path = path(&task);
+                let names = path
+                    .iter()
+                    .rev()
+                    .map(|task| task.data().name.as_str())
+                    .collect::<Vec<_>>();
+                let prefix = names.join(self.separator.unwrap_or(f.glyphs().separator));
+                writeln!(f, "{} {message}", f.style(Style::new().dim(), prefix))
+            }
+        }
+    }
+}
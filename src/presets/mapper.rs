@@ -0,0 +1,75 @@
+use std::fmt::Write;
+
+use super::{EventInfo, TaskInfo};
+use crate::TraceMapper;
+
+/// Maps spans and events to the preset [`TaskInfo`] and [`EventInfo`].
+///
+/// A span is named by its `message` field, falling back to the span name;
+/// an event uses its `message` and level. Any other fields are appended as
+/// `key=value` pairs:
+///
+/// ```rust,ignore
+/// info_span!("compile", krate = "serde");   // => "compile krate=serde"
+/// warn!(attempt = 2, "retrying upload");    // => "retrying upload attempt=2"
+/// ```
+pub struct DefaultMapper;
+
+impl TraceMapper for DefaultMapper {
+    type EventData = EventInfo;
+    type TaskData = TaskInfo;
+
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<TaskInfo> {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        let name = visitor.finish(attrs.metadata().name());
+        Some(TaskInfo { name })
+    }
+
+    fn map_event(event: &tracing::Event<'_>) -> Option<EventInfo> {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let message = visitor.finish("");
+        Some(EventInfo::new(event.metadata().level().into(), message))
+    }
+}
+
+/// Collects the `message` field and formats the rest as `key=value`.
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: String,
+}
+
+impl FieldVisitor {
+    /// The message (or `fallback`) followed by the other fields.
+    fn finish(self, fallback: &str) -> String {
+        let mut out = self.message.unwrap_or_else(|| fallback.to_string());
+        if !out.is_empty() && !self.fields.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&self.fields);
+        out
+    }
+
+    fn push(&mut self, field: &tracing::field::Field, value: std::fmt::Arguments<'_>) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={value}", field.name());
+    }
+}
+
+impl tracing::field::Visit for FieldVisitor {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.push(field, format_args!("{value}"));
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.push(field, format_args!("{value:?}"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Ready-to-use renderers for tasks with a name and events with a message.
+//!
+//! All presets share [`TaskInfo`] and [`EventInfo`] as their data types, so
+//! they pair with [`DefaultMapper`] for `tracing` and can be swapped for one
+//! another freely:
+//!
+//! ```rust,ignore
+//! use trace_tally::presets::{DefaultMapper, TreeRenderer};
+//!
+//! let layer = DefaultMapper::inline_layer(TreeRenderer::new(), std::io::stderr());
+//! tracing_subscriber::registry().with(layer).init();
+//! ```
+//!
+//! - [`TreeRenderer`] draws the whole hierarchy with [`TreeIndent`](crate::widgets::TreeIndent)
+//!   connectors, a spinner, elapsed time and the latest events of each task.
+//! - [`CompactRenderer`] draws one line per root task: the path to its
+//!   innermost active subtask and the latest event.
+//! - [`CiRenderer`] draws indented stages that collapse once completed, like
+//!   the `ci` example.
+
+mod ci;
+mod compact;
+#[cfg(feature = "tracing")]
+mod mapper;
+mod tree;
+
+pub use ci::CiRenderer;
+pub use compact::CompactRenderer;
+#[cfg(feature = "tracing")]
+pub use mapper::DefaultMapper;
+pub use tree::TreeRenderer;
+
+use crate::widgets::Spinner;
+use crate::{FrameWriter, Renderer, Style, Styled, TaskView};
+
+/// Task data used by the presets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskInfo {
+    /// Display name of the task.
+    pub name: String,
+}
+
+impl From<&str> for TaskInfo {
+    fn from(name: &str) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl From<String> for TaskInfo {
+    fn from(name: String) -> Self {
+        Self { name }
+    }
+}
+
+/// Event data used by the presets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventInfo {
+    /// Severity, used to pick the event's color.
+    pub level: Level,
+    /// The formatted message.
+    pub message: String,
+}
+
+impl EventInfo {
+    pub fn new(level: Level, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+        }
+    }
+}
+
+/// An [`Level::Info`] event with the given message.
+impl From<&str> for EventInfo {
+    fn from(message: &str) -> Self {
+        Self::new(Level::Info, message)
+    }
+}
+
+/// An [`Level::Info`] event with the given message.
+impl From<String> for EventInfo {
+    fn from(message: String) -> Self {
+        Self::new(Level::Info, message)
+    }
+}
+
+/// Severity of an [`EventInfo`], mirroring `tracing`'s levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// The style the presets draw messages of this level in.
+    pub fn style(self) -> Style {
+        match self {
+            Level::Trace | Level::Debug => Style::new().dim(),
+            Level::Info => Style::new(),
+            Level::Warn => Style::new().yellow(),
+            Level::Error => Style::new().red().bold(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<&tracing::Level> for Level {
+    fn from(level: &tracing::Level) -> Self {
+        match *level {
+            tracing::Level::TRACE => Level::Trace,
+            tracing::Level::DEBUG => Level::Debug,
+            tracing::Level::INFO => Level::Info,
+            tracing::Level::WARN => Level::Warn,
+            tracing::Level::ERROR => Level::Error,
+        }
+    }
+}
+
+/// Status glyph for a task: the spinner while active, a mark once finished.
+fn status<R: Renderer>(
+    f: &FrameWriter<'_>,
+    task: &TaskView<'_, R>,
+    spinner: &Spinner,
+    color: Style,
+) -> Styled<&'static str> {
+    if task.cancelled() {
+        f.style(Style::new().yellow(), f.glyphs().cancelled)
+    } else if task.completed() {
+        f.style(Style::new().green(), f.glyphs().done)
+    } else {
+        f.style(color, spinner.frame())
+    }
+}
+
+/// The message of error-level events, for [`Renderer::error_message`].
+fn error_message(event: &EventInfo) -> Option<String> {
+    (event.level == Level::Error).then(|| event.message.clone())
+}
+
+/// The marker for a task's rate-limited events, e.g. `… suppressed 12 events`.
+fn suppressed<R: Renderer>(f: &FrameWriter<'_>, task: &TaskView<'_, R>) -> String {
+    let ellipsis = f.glyphs().ellipsis;
+    match task.suppressed_events() {
+        1 => format!("{ellipsis} suppressed 1 event"),
+        n => format!("{ellipsis} suppressed {n} events"),
+    }
+}
@@ -0,0 +1,165 @@
+use std::io::Write;
+
+use super::{EventInfo, TaskInfo, status, suppressed};
+use crate::widgets::{Elapsed, Spinner, TreeIndent};
+use crate::{Collapse, EventView, FrameWriter, Renderer, Style, TaskSummary, TaskView};
+
+/// Draws the full task hierarchy with tree connectors.
+///
+/// ```text
+/// ⠹ deploy 4.2s
+/// │ uploading artifacts
+/// ├── ✔ build
+/// └── ⠹ upload 1.3s
+///       eu-west-1: 12/40 files
+/// ```
+///
+/// ```rust,ignore
+/// let renderer = TreeRenderer::new().events(5).elapsed(false);
+/// ```
+pub struct TreeRenderer {
+    spinner: Spinner,
+    events: usize,
+    elapsed: bool,
+    collapse: Collapse,
+}
+
+impl TreeRenderer {
+    pub fn new() -> Self {
+        Self {
+            spinner: Spinner::dots(),
+            events: 3,
+            elapsed: true,
+            collapse: Collapse::Never,
+        }
+    }
+
+    /// Spinner drawn in front of active tasks.
+    pub fn spinner(mut self, spinner: Spinner) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
+    /// Number of most recent events shown under each active task (default 3).
+    pub fn events(mut self, n: usize) -> Self {
+        self.events = n;
+        self
+    }
+
+    /// Whether active tasks show their elapsed time (default `true`).
+    pub fn elapsed(mut self, show: bool) -> Self {
+        self.elapsed = show;
+        self
+    }
+
+    /// When finished tasks fold into a summary line (default [`Collapse::Never`]).
+    pub fn collapse(mut self, collapse: Collapse) -> Self {
+        self.collapse = collapse;
+        self
+    }
+
+    fn write_name(
+        &self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let indent = TreeIndent::of(task).skip_root();
+        let status = status(f, task, &self.spinner, Style::new().cyan());
+        let name = match task.depth() {
+            1 => f.style(Style::new().bold(), &task.data().name),
+            _ => f.style(Style::new(), &task.data().name),
+        };
+        write!(f, "{indent}{status} {name}")
+    }
+}
+
+impl Default for TreeRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for TreeRenderer {
+    type EventData = EventInfo;
+    type TaskData = TaskInfo;
+
+    fn on_render_start(&mut self) {
+        self.spinner.tick();
+    }
+
+    fn max_events(&self) -> usize {
+        self.events
+    }
+
+    fn summary_label(&self, task: &TaskView<'_, Self>) -> Option<String> {
+        Some(task.data().name.clone())
+    }
+
+    fn error_message(&self, event: &EventView<'_, Self>) -> Option<String> {
+        super::error_message(event.data())
+    }
+
+    fn render_task_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        self.write_name(f, task)?;
+        if self.elapsed && task.active() {
+            write!(f, " {}", f.style(Style::new().dim(), Elapsed::of(task)))?;
+        }
+        writeln!(f)
+    }
+
+    fn collapse(&self, _task: &TaskView<'_, Self>) -> Collapse {
+        self.collapse
+    }
+
+    fn render_summary_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+        summary: &TaskSummary,
+    ) -> std::io::Result<()> {
+        self.write_name(f, task)?;
+        writeln!(f, " {}", f.style(Style::new().dim(), summary))
+    }
+
+    fn render_event_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        event: &EventView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let data = event.data();
+        let message = f.style(data.level.style(), &data.message);
+        if event.is_root() {
+            return writeln!(f, "{message}");
+        }
+        let task = event.task();
+        let indent = TreeIndent::below(&task).skip_root();
+        // Keep the line down to the task's children unbroken.
+        let rail = match task.subtasks().len() {
+            0 => " ",
+            _ => f.glyphs().rail,
+        };
+        writeln!(f, "{indent}{} {message}", f.style(Style::new().dim(), rail))
+    }
+
+    fn render_suppressed_line(
+        &mut self,
+        f: &mut FrameWriter<'_>,
+        task: &TaskView<'_, Self>,
+    ) -> std::io::Result<()> {
+        let marker = suppressed(f, task);
+        let marker = f.style(Style::new().dim(), marker);
+        if task.id().is_root() {
+            return writeln!(f, "{marker}");
+        }
+        let indent = TreeIndent::below(task).skip_root();
+        let rail = match task.subtasks().len() {
+            0 => " ",
+            _ => f.glyphs().rail,
+        };
+        writeln!(f, "{indent}{} {marker}", f.style(Style::new().dim(), rail))
+    }
+}
@@ -0,0 +1,219 @@
+//! Drawing the task tree inside a ratatui UI.
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::{Color as TuiColor, Modifier, Style as TuiStyle};
+use ratatui_core::widgets::{StatefulWidget, Widget};
+
+use crate::{Color, Line, Renderer, Style, TaskRenderer};
+
+/// Draws a [`TaskRenderer`]'s tree into a ratatui [`Buffer`] region.
+///
+/// Full-screen apps can't share the terminal with the inline frame, so
+/// instead of [`TaskRenderer::render`] they draw the same tree with this
+/// widget. The [`Renderer`] and the tracing layer feeding it stay unchanged:
+/// drain actions into the [`TaskRenderer`] on each tick and render the
+/// widget in your layout.
+///
+/// ```rust,ignore
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// tracing_subscriber::registry()
+///     .with(MyMapper::channel_layer(tx))
+///     .init();
+///
+/// let mut tasks = TaskRenderer::new(TreeRenderer::new());
+/// loop {
+///     while let Ok(action) = rx.try_recv() {
+///         tasks.update(action);
+///     }
+///     terminal.draw(|frame| {
+///         frame.render_widget(TaskTreeWidget::new(&mut tasks), frame.area());
+///     })?;
+/// }
+/// ```
+///
+/// The tree is rendered with [`TaskRenderer::render_lines`], so it is cut to
+/// the area's width and clipped to its height like the inline frame, and
+/// finished tasks stay in the tree. Render it with a [`TaskTreeState`] to
+/// scroll through the whole tree instead.
+pub struct TaskTreeWidget<'a, R: Renderer> {
+    renderer: &'a mut TaskRenderer<R>,
+    highlight: TuiStyle,
+}
+
+impl<'a, R: Renderer> TaskTreeWidget<'a, R> {
+    pub fn new(renderer: &'a mut TaskRenderer<R>) -> Self {
+        Self {
+            renderer,
+            highlight: TuiStyle::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Style patched onto the selected line when rendered with a
+    /// [`TaskTreeState`] (default reversed).
+    pub fn highlight_style(mut self, style: impl Into<TuiStyle>) -> Self {
+        self.highlight = style.into();
+        self
+    }
+}
+
+impl<R: Renderer> Widget for TaskTreeWidget<'_, R> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let (width, height) = (area.width as usize, area.height as usize);
+        let Ok(lines) = self.renderer.render_lines(width, height) else {
+            return;
+        };
+        for (line, y) in lines.iter().zip(area.top()..area.bottom()) {
+            draw_line(buf, area, y, line);
+        }
+    }
+}
+
+/// Scroll position and selected line of a [`TaskTreeWidget`].
+///
+/// With a state the whole tree is rendered and scrolled through instead of
+/// being clipped to the area.
+///
+/// ```rust,ignore
+/// let mut state = TaskTreeState::default();
+/// state.select_next();
+/// frame.render_stateful_widget(TaskTreeWidget::new(&mut tasks), area, &mut state);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskTreeState {
+    offset: usize,
+    selected: Option<usize>,
+    len: usize,
+}
+
+impl TaskTreeState {
+    /// Index of the first visible line.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Index of the selected line, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the line at `index`, or clears the selection.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index;
+    }
+
+    /// Moves the selection down one line, stopping at the last.
+    pub fn select_next(&mut self) {
+        let last = self.len.saturating_sub(1);
+        self.selected = Some(self.selected.map_or(0, |i| (i + 1).min(last)));
+    }
+
+    /// Moves the selection up one line, stopping at the first.
+    pub fn select_previous(&mut self) {
+        self.selected = Some(self.selected.map_or(0, |i| i.saturating_sub(1)));
+    }
+
+    /// Scrolls the view down by `lines`.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.offset = (self.offset + lines).min(self.len.saturating_sub(1));
+    }
+
+    /// Scrolls the view up by `lines`.
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_sub(lines);
+    }
+}
+
+impl<R: Renderer> StatefulWidget for TaskTreeWidget<'_, R> {
+    type State = TaskTreeState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TaskTreeState) {
+        // Layouts routinely hand out empty areas; there's no row to keep the
+        // selection in.
+        if area.is_empty() {
+            return;
+        }
+        let Ok(lines) = self.renderer.render_lines(area.width as usize, usize::MAX) else {
+            return;
+        };
+        let height = area.height as usize;
+        state.len = lines.len();
+        state.selected = state.selected.map(|i| i.min(lines.len().saturating_sub(1)));
+        state.offset = state.offset.min(lines.len().saturating_sub(height));
+        if let Some(selected) = state.selected {
+            // Keep the selected line in view.
+            state.offset = state
+                .offset
+                .clamp((selected + 1).saturating_sub(height), selected);
+        }
+
+        let visible = lines.iter().enumerate().skip(state.offset);
+        for ((index, line), y) in visible.zip(area.top()..area.bottom()) {
+            draw_line(buf, area, y, line);
+            if state.selected == Some(index) {
+                let row = Rect::new(area.x, y, area.width, 1);
+                buf.set_style(row, self.highlight);
+            }
+        }
+    }
+}
+
+/// Draws `line` on row `y` of `area`.
+fn draw_line(buf: &mut Buffer, area: Rect, y: u16, line: &Line) {
+    let mut x = area.x;
+    for (text, style) in line.styled() {
+        let width = area.right().saturating_sub(x) as usize;
+        (x, _) = buf.set_stringn(x, y, text, width, style);
+    }
+}
+
+impl From<Color> for TuiColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => TuiColor::Black,
+            Color::Red => TuiColor::Red,
+            Color::Green => TuiColor::Green,
+            Color::Yellow => TuiColor::Yellow,
+            Color::Blue => TuiColor::Blue,
+            Color::Magenta => TuiColor::Magenta,
+            Color::Cyan => TuiColor::Cyan,
+            Color::White => TuiColor::Gray,
+            Color::BrightBlack => TuiColor::DarkGray,
+            Color::BrightRed => TuiColor::LightRed,
+            Color::BrightGreen => TuiColor::LightGreen,
+            Color::BrightYellow => TuiColor::LightYellow,
+            Color::BrightBlue => TuiColor::LightBlue,
+            Color::BrightMagenta => TuiColor::LightMagenta,
+            Color::BrightCyan => TuiColor::LightCyan,
+            Color::BrightWhite => TuiColor::White,
+            Color::Ansi256(n) => TuiColor::Indexed(n),
+            Color::Rgb(r, g, b) => TuiColor::Rgb(r, g, b),
+        }
+    }
+}
+
+impl From<Style> for TuiStyle {
+    fn from(style: Style) -> Self {
+        let mut out = TuiStyle::new();
+        if let Some(fg) = style.fg_color() {
+            out = out.fg(fg.into());
+        }
+        if let Some(bg) = style.bg_color() {
+            out = out.bg(bg.into());
+        }
+        let modifiers = [
+            (style.is_bold(), Modifier::BOLD),
+            (style.is_dim(), Modifier::DIM),
+            (style.is_italic(), Modifier::ITALIC),
+            (style.is_underline(), Modifier::UNDERLINED),
+            (style.is_reversed(), Modifier::REVERSED),
+        ];
+        for (_, modifier) in modifiers.into_iter().filter(|(on, _)| *on) {
+            out = out.add_modifier(modifier);
+        }
+        out
+    }
+}
@@ -1,7 +1,11 @@
 use std::io::Write;
-use std::time::Duration;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
-use crate::{Action, Renderer, TaskRenderer};
+use crate::{Action, OutputMode, Renderer, RunSummary, TaskRenderer, TaskView, term};
+
+/// How often [`RenderLoop`] checks for a terminal resize between frames.
+const RESIZE_POLL: Duration = Duration::from_millis(50);
 
 /// Drain available actions from a channel or queue into a [`TaskRenderer`].
 ///
@@ -60,16 +64,95 @@ pub struct RenderLoop<R: Renderer, W: Write> {
     writer: W,
     interval: Duration,
     cancel_on_close: bool,
+    hide_cursor: bool,
+    cursor_hidden: bool,
+    requests: mpsc::Receiver<SuspendRequest>,
+    handle: RenderHandle,
+}
+
+type SuspendRequest = Box<dyn FnOnce(&mut dyn Write) + Send>;
+
+/// A cloneable handle for writing above a running [`RenderLoop`]'s frame.
+///
+/// Obtained from [`RenderLoop::handle`]. Requests are executed on the render
+/// thread between frames: the active frame is erased, the closure runs with
+/// the loop's writer, and the frame is redrawn below its output.
+///
+/// Calls block until the render loop has served the request, so they must
+/// not be made from inside a [`Renderer`] callback.
+///
+/// Under [`RenderLoop::run_async`], requests are only served after each
+/// `wait_fn` future resolves, so a call blocks for up to one interval and
+/// deadlocks when made from a task on the loop's own current-thread runtime.
+/// Use [`defer`](Self::defer) from async code instead.
+///
+/// ```rust,ignore
+/// let render_loop = RenderLoop::new(MyRenderer::default(), std::io::stderr());
+/// let handle = render_loop.handle();
+/// std::thread::spawn(move || render_loop.run(rx));
+///
+/// // From any thread:
+/// handle.println_above("warning: disk almost full");
+/// ```
+#[derive(Clone)]
+pub struct RenderHandle {
+    tx: mpsc::Sender<SuspendRequest>,
+}
+
+impl RenderHandle {
+    /// Erases the frame, runs `f` with the loop's writer, and redraws.
+    ///
+    /// Returns `None` if the render loop has already exited (or was dropped
+    /// without running), in which case `f` is not called.
+    ///
+    /// Blocks until the loop serves the request; see the
+    /// [type-level docs](Self) for why not to call this from async code.
+    pub fn suspend<T, F>(&self, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut dyn Write) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+        let request: SuspendRequest = Box::new(move |w| {
+            let _ = reply_tx.send(f(w));
+        });
+        self.tx.send(request).ok()?;
+        reply_rx.recv().ok()
+    }
+
+    /// Like [`suspend`](Self::suspend), but queues `f` and returns right
+    /// away instead of waiting for the loop to run it.
+    ///
+    /// Returns `false` if the render loop is no longer running.
+    pub fn defer<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(&mut dyn Write) + Send + 'static,
+    {
+        self.tx.send(Box::new(f)).is_ok()
+    }
+
+    /// Prints `line` above the active frame, followed by a newline.
+    ///
+    /// Returns `false` if the render loop is no longer running.
+    pub fn println_above(&self, line: impl std::fmt::Display) -> bool {
+        let line = line.to_string();
+        self.suspend(move |w| writeln!(w, "{line}")).is_some()
+    }
 }
 
 impl<R: Renderer, W: Write> RenderLoop<R, W> {
     /// Create a loop with sensible defaults (100 ms interval, cancel on close).
     pub fn new(r: R, writer: W) -> Self {
+        let (tx, requests) = mpsc::channel();
         Self {
-            renderer: TaskRenderer::new(r),
+            renderer: TaskRenderer::new(r).mode(OutputMode::Auto),
             writer,
             interval: Duration::from_millis(100),
             cancel_on_close: true,
+            hide_cursor: true,
+            cursor_hidden: false,
+            requests,
+            handle: RenderHandle { tx },
         }
     }
 
@@ -86,11 +169,60 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
         self
     }
 
+    /// Whether to hide the terminal cursor while the loop is running.
+    ///
+    /// Enabled by default (in interactive mode only). The cursor is shown
+    /// again when the loop finishes, when it is dropped, and on panic.
+    pub fn hide_cursor(mut self, yes: bool) -> Self {
+        self.hide_cursor = yes;
+        self
+    }
+
+    /// Choose between redrawing in place and append-only output.
+    /// See [`TaskRenderer::mode`].
+    ///
+    /// Defaults to [`OutputMode::Auto`], which suits the usual stderr
+    /// writer. Pass [`OutputMode::detect`] of the writer for other streams.
+    pub fn mode(mut self, mode: OutputMode) -> Self {
+        self.renderer.set_mode(mode);
+        self
+    }
+
+    /// Move finished tasks up to `depth` levels deep to the scrollback.
+    /// See [`TaskRenderer::commit_depth`].
+    pub fn commit_depth(mut self, depth: usize) -> Self {
+        self.renderer.set_commit_depth(depth);
+        self
+    }
+
+    /// Move the finished tasks for which `f` returns `true` to the
+    /// scrollback. See [`TaskRenderer::commit_when`].
+    pub fn commit_when(
+        mut self,
+        f: impl Fn(&TaskView<'_, R>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.renderer.set_commit_when(f);
+        self
+    }
+
     /// Borrow the inner [`TaskRenderer`] (e.g. to snapshot state).
     pub fn renderer(&self) -> &TaskRenderer<R> {
         &self.renderer
     }
 
+    /// Returns a [`RenderHandle`] for printing above the frame from other threads.
+    pub fn handle(&self) -> RenderHandle {
+        self.handle.clone()
+    }
+
+    /// Erases the frame, runs `f` with the loop's writer, and redraws.
+    ///
+    /// The single-threaded counterpart of [`RenderHandle::suspend`], for use
+    /// with a custom outer loop built on [`tick`](Self::tick).
+    pub fn suspend<T>(&mut self, f: impl FnOnce(&mut dyn Write) -> T) -> std::io::Result<T> {
+        self.renderer.suspend(&mut self.writer, f)
+    }
+
     /// Run the loop until the source closes. Blocks the calling thread.
     ///
     /// Each cycle drains all buffered actions, renders a frame, then sleeps
@@ -98,6 +230,9 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
     /// drain + render occurs, preceded by [`Action::CancelAll`] if
     /// [`cancel_on_close`](Self::cancel_on_close) is enabled.
     ///
+    /// Returns the [`RunSummary`], after printing it with
+    /// [`Renderer::render_run_summary`] below the final frame.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
@@ -110,7 +245,8 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
     /// // Dropping all senders closes the channel and stops the loop.
     /// drop(tx);
     /// ```
-    pub fn run(mut self, mut source: impl ActionSource<R>) {
+    pub fn run(mut self, mut source: impl ActionSource<R>) -> RunSummary {
+        self.enter();
         loop {
             let alive = source.drain_into(&mut self.renderer);
             // Ignore render errors — stderr can't really fail in practice,
@@ -119,19 +255,17 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
             if !alive {
                 break;
             }
-            std::thread::sleep(self.interval);
+            self.wait();
         }
 
-        if self.cancel_on_close {
-            self.renderer.update(Action::CancelAll);
-            let _ = self.renderer.render(&mut self.writer);
-        }
+        self.finish()
     }
 
     /// Like [`run`](Self::run), but also exits when `stop` returns `true`.
     ///
     /// The predicate is checked after each drain-and-render cycle,
-    /// so the final frame always reflects the latest actions.
+    /// so the final frame always reflects the latest actions. Returns the
+    /// [`RunSummary`] like [`run`](Self::run).
     ///
     /// # Example
     ///
@@ -150,25 +284,28 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
     /// // Later, signal the render loop to stop:
     /// stop.store(true, Ordering::Relaxed);
     /// ```
-    pub fn run_until(mut self, mut source: impl ActionSource<R>, stop: impl Fn() -> bool) {
+    pub fn run_until(
+        mut self,
+        mut source: impl ActionSource<R>,
+        stop: impl Fn() -> bool,
+    ) -> RunSummary {
+        self.enter();
         loop {
             let alive = source.drain_into(&mut self.renderer);
             let _ = self.renderer.render(&mut self.writer);
             if !alive || stop() {
                 break;
             }
-            std::thread::sleep(self.interval);
-        }
-        if self.cancel_on_close {
-            self.renderer.update(Action::CancelAll);
-            let _ = self.renderer.render(&mut self.writer);
+            self.wait();
         }
+        self.finish()
     }
 
     /// Run the loop asynchronously.
     ///
     /// The `wait_fn` closure must return a future that resolves to `true` to
-    /// continue the loop, or `false` to abort immediately.
+    /// continue the loop, or `false` to abort immediately. Returns the
+    /// [`RunSummary`] like [`run`](Self::run).
     ///
     /// # Example
     ///
@@ -188,12 +325,13 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
     ///     }
     /// }).await;
     /// ```
-    pub async fn run_async<S, D, F>(mut self, mut source: S, mut wait_fn: D)
+    pub async fn run_async<S, D, F>(mut self, mut source: S, mut wait_fn: D) -> RunSummary
     where
         S: ActionSource<R>,
         D: FnMut(Duration) -> F,
         F: Future<Output = bool>,
     {
+        self.enter();
         loop {
             let alive = source.drain_into(&mut self.renderer);
             let _ = self.renderer.render(&mut self.writer);
@@ -205,12 +343,10 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
             if !wait_fn(self.interval).await {
                 break;
             }
+            self.serve_requests(None);
         }
 
-        if self.cancel_on_close {
-            self.renderer.update(Action::CancelAll);
-            let _ = self.renderer.render(&mut self.writer);
-        }
+        self.finish()
     }
 
     /// Run a single tick: drain + render. Returns `false` when the source
@@ -219,8 +355,86 @@ impl<R: Renderer, W: Write> RenderLoop<R, W> {
     /// Use this if you need a custom outer loop (e.g. checking additional
     /// shutdown conditions) but still want the drain-then-render logic.
     pub fn tick(&mut self, source: &mut impl ActionSource<R>) -> bool {
+        self.enter();
+        self.serve_requests(None);
         let alive = source.drain_into(&mut self.renderer);
         let _ = self.renderer.render(&mut self.writer);
         alive
     }
+
+    /// Wraps up a run: serves pending requests, cancels what's left if
+    /// configured, then prints the summary below the final frame.
+    fn finish(&mut self) -> RunSummary {
+        self.serve_requests(None);
+        if self.cancel_on_close {
+            self.renderer.update(Action::CancelAll);
+            let _ = self.renderer.render(&mut self.writer);
+        }
+        let summary = self.renderer.summary();
+        let _ = self.renderer.render_summary(&mut self.writer, &summary);
+        self.leave();
+        summary
+    }
+
+    /// Hides the cursor for the duration of the loop, if configured.
+    fn enter(&mut self) {
+        if self.hide_cursor && !self.cursor_hidden && self.renderer.is_interactive() {
+            self.cursor_hidden = term::hide_cursor(&mut self.writer).is_ok();
+        }
+    }
+
+    /// Shows the cursor again if [`enter`](Self::enter) hid it.
+    fn leave(&mut self) {
+        if std::mem::take(&mut self.cursor_hidden) {
+            let _ = term::show_cursor(&mut self.writer);
+        }
+    }
+
+    /// Sleeps for one interval, waking early to serve [`RenderHandle`] requests.
+    ///
+    /// Returns early when the terminal is resized, so the frame is redrawn
+    /// at the new size without waiting out a long interval.
+    fn wait(&mut self) {
+        let deadline = Instant::now() + self.interval;
+        let watch_size = self.renderer.is_interactive();
+        let size = watch_size.then(term::terminal_size);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return;
+            }
+            let timeout = match watch_size {
+                true => remaining.min(RESIZE_POLL),
+                false => remaining,
+            };
+            match self.requests.recv_timeout(timeout) {
+                Ok(request) => self.serve_requests(Some(request)),
+                Err(RecvTimeoutError::Timeout) if watch_size => {
+                    if term::terminal_size() != size.flatten() {
+                        return;
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Serves `first` plus any pending [`RenderHandle`] requests in a single
+    /// suspend, without blocking.
+    fn serve_requests(&mut self, first: Option<SuspendRequest>) {
+        let mut pending = first.into_iter().chain(self.requests.try_iter()).peekable();
+        if pending.peek().is_some() {
+            let _ = self.renderer.suspend(&mut self.writer, |w| {
+                for request in pending {
+                    request(w);
+                }
+            });
+        }
+    }
+}
+
+impl<R: Renderer, W: Write> Drop for RenderLoop<R, W> {
+    fn drop(&mut self) {
+        self.leave();
+    }
 }
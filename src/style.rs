@@ -0,0 +1,480 @@
+use std::cell::Cell;
+use std::fmt::Display;
+
+/// How many colors the output target can display.
+///
+/// Detected from the environment by [`ColorSupport::detect`] and overridable
+/// with [`TaskRenderer::color`](crate::TaskRenderer::color). A [`Style`] is
+/// downgraded to the closest colors the target supports, or dropped entirely
+/// for [`ColorSupport::None`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ColorSupport {
+    /// No escape sequences at all.
+    #[default]
+    None,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detects color support for stderr from the environment.
+    ///
+    /// - `NO_COLOR` (non-empty) disables color.
+    /// - `CLICOLOR_FORCE` (non-empty, not `0`) enables color even when stderr
+    ///   isn't a terminal.
+    /// - `TERM=dumb` or a non-terminal stderr disables color.
+    /// - `COLORTERM=truecolor|24bit` selects [`TrueColor`](Self::TrueColor),
+    ///   a `TERM` containing `256color` selects [`Ansi256`](Self::Ansi256).
+    pub fn detect() -> Self {
+        Self::detect_for(&std::io::stderr())
+    }
+
+    /// Like [`detect`](Self::detect), but checks whether `stream` is a terminal.
+    pub fn detect_for(stream: &impl std::io::IsTerminal) -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        if var("NO_COLOR").is_some() {
+            return Self::None;
+        }
+        let forced = var("CLICOLOR_FORCE").is_some_and(|v| v != "0");
+        let term = var("TERM").unwrap_or_default();
+        if !forced && (term == "dumb" || !stream.is_terminal()) {
+            return Self::None;
+        }
+        match var("COLORTERM").as_deref() {
+            Some("truecolor" | "24bit") => Self::TrueColor,
+            _ if term.contains("256color") => Self::Ansi256,
+            _ => Self::Ansi16,
+        }
+    }
+
+    /// The support widgets should resolve their styles against right now.
+    ///
+    /// Inside a render this is the rendering [`FrameWriter`](crate::FrameWriter)'s
+    /// support; elsewhere it is [`None`](Self::None), so widgets formatted
+    /// outside a renderer never emit escape sequences.
+    pub fn current() -> Self {
+        CURRENT.get().unwrap_or_default()
+    }
+}
+
+/// Makes a support [`ColorSupport::current`] on this thread until dropped.
+pub(crate) struct ColorScope(Option<ColorSupport>);
+
+impl ColorScope {
+    pub(crate) fn enter(support: ColorSupport) -> Self {
+        Self(CURRENT.replace(Some(support)))
+    }
+}
+
+impl Drop for ColorScope {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<ColorSupport>> = const { Cell::new(None) };
+}
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// An index into the xterm 256-color palette.
+    Ansi256(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+/// RGB values of the 16 basic colors, as rendered by xterm.
+const BASIC_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl Color {
+    /// Index of this color in the basic 16, if it is one.
+    fn basic(self) -> Option<u8> {
+        use Color::*;
+        let index = match self {
+            Black => 0,
+            Red => 1,
+            Green => 2,
+            Yellow => 3,
+            Blue => 4,
+            Magenta => 5,
+            Cyan => 6,
+            White => 7,
+            BrightBlack => 8,
+            BrightRed => 9,
+            BrightGreen => 10,
+            BrightYellow => 11,
+            BrightBlue => 12,
+            BrightMagenta => 13,
+            BrightCyan => 14,
+            BrightWhite => 15,
+            Ansi256(_) | Rgb(..) => return None,
+        };
+        Some(index)
+    }
+
+    fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Ansi256(n @ 0..=15) => BASIC_RGB[n as usize],
+            Color::Ansi256(n @ 16..=231) => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                let n = n - 16;
+                (level(n / 36), level(n / 6 % 6), level(n % 6))
+            }
+            Color::Ansi256(n) => {
+                let v = 8 + (n - 232) * 10;
+                (v, v, v)
+            }
+            basic => BASIC_RGB[basic.basic().unwrap() as usize],
+        }
+    }
+
+    /// Closest entry in the 256-color palette.
+    fn to_256(self) -> u8 {
+        if let Some(n) = self.basic() {
+            return n;
+        }
+        if let Color::Ansi256(n) = self {
+            return n;
+        }
+        let (r, g, b) = self.rgb();
+        if r == g && g == b {
+            return match r {
+                0..8 => 16,
+                // Past the last grey (238), white is closer.
+                248.. => 231,
+                v => 232 + (v - 8) / 10,
+            };
+        }
+        let step = |v: u8| match v {
+            0..48 => 0,
+            48..115 => 1,
+            v => (v - 35) / 40,
+        };
+        16 + 36 * step(r) + 6 * step(g) + step(b)
+    }
+
+    /// Closest of the 16 basic colors.
+    fn to_16(self) -> u8 {
+        if let Some(n) = self.basic() {
+            return n;
+        }
+        let (r, g, b) = self.rgb();
+        let distance = |&(br, bg, bb): &(u8, u8, u8)| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, br) + d(g, bg) + d(b, bb)
+        };
+        (0..16).min_by_key(|&i| distance(&BASIC_RGB[i])).unwrap() as u8
+    }
+
+    /// Appends the SGR parameters for this color. `base` is 30 for the
+    /// foreground and 40 for the background.
+    fn push_sgr(self, out: &mut String, base: u8, support: ColorSupport) {
+        use std::fmt::Write;
+        let _ = match support {
+            ColorSupport::None => return,
+            ColorSupport::TrueColor if matches!(self, Color::Rgb(..)) => {
+                let (r, g, b) = self.rgb();
+                write!(out, "{};2;{r};{g};{b}", base + 8)
+            }
+            ColorSupport::TrueColor | ColorSupport::Ansi256 if self.basic().is_none() => {
+                write!(out, "{};5;{}", base + 8, self.to_256())
+            }
+            _ => match self.to_16() {
+                n @ 0..8 => write!(out, "{}", base + n),
+                n => write!(out, "{}", base + 60 + n - 8),
+            },
+        };
+    }
+}
+
+/// Text attributes and colors, applied with [`FrameWriter::style`](crate::FrameWriter::style).
+///
+/// ```rust,ignore
+/// write!(f, "{}", f.style(Style::new().green().bold(), "✔"))?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    reversed: bool,
+}
+
+macro_rules! color_methods {
+    ($($name:ident => $color:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Sets the foreground to [`Color::", stringify!($color), "`].")]
+            pub const fn $name(self) -> Self {
+                self.fg(Color::$color)
+            }
+        )*
+    };
+}
+
+impl Style {
+    /// A style with no colors or attributes.
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+            reversed: false,
+        }
+    }
+
+    /// Sets the foreground color.
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Sets the background color.
+    pub const fn on(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    color_methods! {
+        black => Black,
+        red => Red,
+        green => Green,
+        yellow => Yellow,
+        blue => Blue,
+        magenta => Magenta,
+        cyan => Cyan,
+        white => White,
+        gray => BrightBlack,
+    }
+
+    /// Bold (increased intensity) text.
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Dim (decreased intensity) text.
+    pub const fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    /// Italic text.
+    pub const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Underlined text.
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Swapped foreground and background.
+    pub const fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    /// Wraps `value` in this style, resolved for `support`.
+    ///
+    /// Inside a renderer, prefer [`FrameWriter::style`](crate::FrameWriter::style),
+    /// which supplies the writer's detected support.
+    pub fn paint<T: Display>(self, value: T, support: ColorSupport) -> Styled<T> {
+        Styled {
+            style: self,
+            support,
+            value,
+        }
+    }
+
+    /// The foreground color, if set.
+    pub fn fg_color(&self) -> Option<Color> {
+        self.fg
+    }
+
+    /// The background color, if set.
+    pub fn bg_color(&self) -> Option<Color> {
+        self.bg
+    }
+
+    /// Whether the text is bold.
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    /// Whether the text is dim.
+    pub fn is_dim(&self) -> bool {
+        self.dim
+    }
+
+    /// Whether the text is italic.
+    pub fn is_italic(&self) -> bool {
+        self.italic
+    }
+
+    /// Whether the text is underlined.
+    pub fn is_underline(&self) -> bool {
+        self.underline
+    }
+
+    /// Whether foreground and background are swapped.
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Updates this style with the parameters of an SGR escape
+    /// (`ESC [ params m`), as a terminal would.
+    pub(crate) fn apply_sgr(&mut self, params: &str) {
+        let mut params = params.split(';').map(|p| p.parse::<u8>().unwrap_or(0));
+        while let Some(code) = params.next() {
+            match code {
+                0 => *self = Self::new(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.reversed = true,
+                22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.reversed = false,
+                30..=37 => self.fg = basic(code - 30),
+                90..=97 => self.fg = basic(code - 90 + 8),
+                40..=47 => self.bg = basic(code - 40),
+                100..=107 => self.bg = basic(code - 100 + 8),
+                38 => self.fg = extended(&mut params),
+                48 => self.bg = extended(&mut params),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// The SGR parameter list for this style, empty if nothing applies.
+    fn sgr(&self, support: ColorSupport) -> String {
+        let mut out = String::new();
+        if support == ColorSupport::None {
+            return out;
+        }
+        let attrs = [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.reversed, "7"),
+        ];
+        for (_, code) in attrs.iter().filter(|(on, _)| *on) {
+            out.push_str(code);
+            out.push(';');
+        }
+        for (color, base) in [(self.fg, 30), (self.bg, 40)] {
+            if let Some(color) = color {
+                color.push_sgr(&mut out, base, support);
+                out.push(';');
+            }
+        }
+        out.pop();
+        out
+    }
+}
+
+/// The basic color at `index` (0-15).
+fn basic(index: u8) -> Option<Color> {
+    use Color::*;
+    let colors = [
+        Black,
+        Red,
+        Green,
+        Yellow,
+        Blue,
+        Magenta,
+        Cyan,
+        White,
+        BrightBlack,
+        BrightRed,
+        BrightGreen,
+        BrightYellow,
+        BrightBlue,
+        BrightMagenta,
+        BrightCyan,
+        BrightWhite,
+    ];
+    colors.get(index as usize).copied()
+}
+
+/// Reads the `5;n` or `2;r;g;b` color following an SGR 38 or 48.
+fn extended(params: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match params.next()? {
+        5 => Some(Color::Ansi256(params.next()?)),
+        2 => Some(Color::Rgb(params.next()?, params.next()?, params.next()?)),
+        _ => None,
+    }
+}
+
+/// A value wrapped in a [`Style`], displayed with the matching escape codes.
+#[derive(Debug, Clone, Copy)]
+pub struct Styled<T> {
+    style: Style,
+    support: ColorSupport,
+    value: T,
+}
+
+impl<T: Display> Display for Styled<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sgr = self.style.sgr(self.support);
+        if sgr.is_empty() {
+            return self.value.fmt(f);
+        }
+        write!(f, "\x1b[{sgr}m")?;
+        self.value.fmt(f)?;
+        f.write_str("\x1b[0m")
+    }
+}
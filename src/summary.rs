@@ -0,0 +1,172 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::{EventView, HumanDuration, Renderer, TaskId, TaskView};
+
+/// How many of the slowest tasks a [`RunSummary`] lists.
+const SLOWEST: usize = 5;
+/// How many of the most recent error messages a [`RunSummary`] keeps.
+const LAST_ERRORS: usize = 5;
+
+/// Totals for a whole run, returned by [`RenderLoop::run`](crate::RenderLoop::run)
+/// and [`TaskRenderer::summary`](crate::TaskRenderer::summary).
+///
+/// Failures and the slowest tasks are identified through
+/// [`Renderer::error_message`] and [`Renderer::summary_label`].
+///
+/// ```rust,ignore
+/// let summary = RenderLoop::new(MyRenderer::default(), std::io::stderr()).run(rx);
+/// std::process::exit(if summary.success() { 0 } else { 1 });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RunSummary {
+    /// Tasks started.
+    pub started: usize,
+    /// Tasks that ended without an error event.
+    pub completed: usize,
+    /// Tasks that ended after reporting an error event.
+    pub failed: usize,
+    /// Tasks still running when the run was cancelled.
+    pub cancelled: usize,
+    /// Time since the renderer was created.
+    pub wall_time: Duration,
+    /// Up to 5 of the slowest finished tasks, slowest first.
+    pub slowest: Vec<(String, Duration)>,
+    /// Up to 5 of the most recent error messages, oldest first.
+    pub errors: Vec<String>,
+}
+
+impl RunSummary {
+    /// Returns `true` if no task failed and no error event was reported.
+    pub fn success(&self) -> bool {
+        self.failed == 0 && self.errors.is_empty()
+    }
+}
+
+/// ```text
+/// 12 tasks in 4.1s: 10 completed, 1 failed, 1 cancelled
+/// slowest: build 2.1s, test 1.0s
+/// error: connection refused
+/// ```
+impl Display for RunSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tasks = match self.started {
+            1 => "task",
+            _ => "tasks",
+        };
+        let wall_time = HumanDuration::new(self.wall_time);
+        write!(f, "{} {tasks} in {wall_time}", self.started)?;
+        let counts = [
+            (self.completed, "completed"),
+            (self.failed, "failed"),
+            (self.cancelled, "cancelled"),
+        ];
+        let mut counts = counts.iter().filter(|(n, _)| *n > 0);
+        if let Some((n, label)) = counts.next() {
+            write!(f, ": {n} {label}")?;
+            for (n, label) in counts {
+                write!(f, ", {n} {label}")?;
+            }
+        }
+        for (i, (label, duration)) in self.slowest.iter().enumerate() {
+            let sep = if i == 0 { "\nslowest: " } else { ", " };
+            write!(f, "{sep}{label} {}", HumanDuration::new(*duration))?;
+        }
+        for error in &self.errors {
+            write!(f, "\nerror: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Running totals behind a [`RunSummary`], updated as actions are applied.
+#[derive(Debug, Clone)]
+pub(crate) struct RunStats {
+    created_at: Instant,
+    started: usize,
+    completed: usize,
+    failed: usize,
+    cancelled: usize,
+    /// Active tasks that have reported an error event.
+    failing: HashSet<TaskId>,
+    slowest: Vec<(String, Duration)>,
+    errors: VecDeque<String>,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            created_at: Instant::now(),
+            started: 0,
+            completed: 0,
+            failed: 0,
+            cancelled: 0,
+            failing: HashSet::new(),
+            slowest: Vec::new(),
+            errors: VecDeque::new(),
+        }
+    }
+}
+
+impl RunStats {
+    pub(crate) fn task_started(&mut self) {
+        self.started += 1;
+    }
+
+    /// Records a task that just completed, or was cancelled if `cancelled`.
+    pub(crate) fn task_ended<R: Renderer>(
+        &mut self,
+        r: &R,
+        task: &TaskView<'_, R>,
+        cancelled: bool,
+    ) {
+        let failed = self.failing.remove(&task.id());
+        if cancelled {
+            self.cancelled += 1;
+            return;
+        }
+        match failed {
+            true => self.failed += 1,
+            false => self.completed += 1,
+        }
+
+        let Some(label) = r.summary_label(task) else {
+            return;
+        };
+        let duration = task.duration();
+        let fits =
+            self.slowest.len() < SLOWEST || self.slowest.last().is_some_and(|(_, d)| duration > *d);
+        if fits {
+            let index = self.slowest.partition_point(|(_, d)| *d >= duration);
+            self.slowest.insert(index, (label, duration));
+            self.slowest.truncate(SLOWEST);
+        }
+    }
+
+    /// Records a newly buffered event, if the renderer reports it as an error.
+    pub(crate) fn event<R: Renderer>(&mut self, r: &R, event: &EventView<'_, R>) {
+        let Some(message) = r.error_message(event) else {
+            return;
+        };
+        if !event.is_root() {
+            self.failing.insert(event.task().id());
+        }
+        if self.errors.len() == LAST_ERRORS {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(message);
+    }
+
+    pub(crate) fn summary(&self) -> RunSummary {
+        RunSummary {
+            started: self.started,
+            completed: self.completed,
+            failed: self.failed,
+            cancelled: self.cancelled,
+            wall_time: self.created_at.elapsed(),
+            slowest: self.slowest.clone(),
+            errors: self.errors.iter().cloned().collect(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use std::{collections::VecDeque, time::Instant};
 
@@ -40,6 +41,8 @@ pub(crate) struct EventIndex(pub(crate) usize);
 pub struct TaskStore<R: Renderer> {
     pub(crate) tasks: IndexMap<TaskId, Task<R>>,
     pub(crate) max_events: usize,
+    /// Tasks left out of the current frame because it was clipped.
+    pub(crate) hidden: HashSet<TaskId>,
 }
 
 impl<R: Renderer> Clone for TaskStore<R>
@@ -50,6 +53,7 @@ where
         Self {
             tasks: self.tasks.clone(),
             max_events: self.max_events,
+            hidden: self.hidden.clone(),
         }
     }
 }
@@ -75,7 +79,11 @@ impl<R: Renderer> TaskStore<R> {
     pub(crate) fn with_max_events(max_events: usize) -> Self {
         let mut tasks = IndexMap::new();
         tasks.insert(TaskId::ROOT, Task::new(0, None, None));
-        Self { tasks, max_events }
+        Self {
+            tasks,
+            max_events,
+            hidden: HashSet::new(),
+        }
     }
 
     pub(crate) fn root(&mut self) -> &mut Task<R> {
@@ -103,8 +111,11 @@ impl<R: Renderer> TaskStore<R> {
                 let max_events = self.max_events;
                 if let Some(task) = self.get_task_mut(parent) {
                     task.events.push_back(data);
+                    task.event_times.push_back(Instant::now());
                     while task.events.len() > max_events {
                         task.events.pop_front();
+                        task.event_times.pop_front();
+                        task.dropped += 1;
                     }
                 }
             }
@@ -129,9 +140,15 @@ impl<R: Renderer> TaskStore<R> {
 
                 p_task.subtasks.insert(id);
             }
+            Action::EventsSuppressed { parent, count } => {
+                if let Some(task) = self.get_task_mut(parent) {
+                    task.suppressed += count;
+                }
+            }
             Action::TaskEnd { id } => {
                 if let Some(task) = self.get_task_mut(Some(id)) {
                     task.completed = true;
+                    task.finished_at.get_or_insert_with(Instant::now);
                 }
             }
             Action::CancelAll => {
@@ -144,6 +161,7 @@ impl<R: Renderer> TaskStore<R> {
                 while let Some(id) = queue.pop_front() {
                     let task = self.tasks.get_mut(&id).unwrap();
                     task.cancelled = true;
+                    task.finished_at.get_or_insert_with(Instant::now);
                     queue.extend(task.subtasks.iter());
                 }
             }
@@ -164,9 +182,16 @@ pub struct Task<R: Renderer> {
     pub(crate) completed: bool,
     pub(crate) cancelled: bool,
     pub(crate) started_at: Instant,
+    /// When the task completed or was cancelled.
+    pub(crate) finished_at: Option<Instant>,
+    pub(crate) suppressed: usize,
     pub(crate) parent: Option<TaskId>,
     pub(crate) data: Option<R::TaskData>,
     pub(crate) events: VecDeque<R::EventData>,
+    /// When each of `events` was received.
+    pub(crate) event_times: VecDeque<Instant>,
+    /// Number of events removed from the front of `events` so far.
+    pub(crate) dropped: usize,
     pub(crate) subtasks: IndexSet<TaskId>,
 }
 
@@ -196,7 +221,11 @@ impl<R: Renderer> Task<R> {
             completed: false,
             cancelled: false,
             started_at: Instant::now(),
+            finished_at: None,
+            suppressed: 0,
             events: VecDeque::new(),
+            event_times: VecDeque::new(),
+            dropped: 0,
             subtasks: IndexSet::new(),
         }
     }
@@ -205,8 +234,21 @@ impl<R: Renderer> Task<R> {
         &self.events
     }
 
+    /// Sequence number of the most recently buffered event, if any.
+    pub(crate) fn last_event_seq(&self) -> Option<usize> {
+        (self.dropped + self.events.len()).checked_sub(1)
+    }
+
+    /// Buffer index of the event with sequence number `seq`, if still retained.
+    pub(crate) fn event_index(&self, seq: usize) -> Option<usize> {
+        seq.checked_sub(self.dropped)
+            .filter(|&i| i < self.events.len())
+    }
+
     pub(crate) fn clear_events(&mut self) {
+        self.dropped += self.events.len();
         self.events.clear();
+        self.event_times.clear();
     }
 
     pub(crate) fn subtasks(&self) -> &IndexSet<TaskId> {
@@ -0,0 +1,112 @@
+//! Terminal capability queries and mode control.
+
+use std::io::Write;
+use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Fallback width when the terminal size can't be determined.
+pub(crate) const DEFAULT_WIDTH: usize = 80;
+
+/// Begins a synchronized update (DEC mode 2026).
+pub(crate) const SYNC_BEGIN: &[u8] = b"\x1b[?2026h";
+/// Ends a synchronized update, letting the terminal paint.
+pub(crate) const SYNC_END: &[u8] = b"\x1b[?2026l";
+
+const HIDE_CURSOR: &[u8] = b"\x1b[?25l";
+const SHOW_CURSOR: &[u8] = b"\x1b[?25h";
+
+/// Number of writers currently keeping the cursor hidden.
+static CURSOR_HIDDEN: AtomicUsize = AtomicUsize::new(0);
+
+/// Hides the cursor on `w` until a matching [`show_cursor`].
+///
+/// Installs (once) a panic hook that shows the cursor on stderr if any
+/// writer still has it hidden, so a panic never leaves the shell cursorless.
+pub(crate) fn hide_cursor(w: &mut dyn Write) -> std::io::Result<()> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CURSOR_HIDDEN.load(Ordering::SeqCst) > 0 {
+                let _ = std::io::stderr().write_all(SHOW_CURSOR);
+            }
+            previous(info);
+        }));
+    });
+    w.write_all(HIDE_CURSOR)?;
+    w.flush()?;
+    CURSOR_HIDDEN.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Shows the cursor hidden by [`hide_cursor`].
+pub(crate) fn show_cursor(w: &mut dyn Write) -> std::io::Result<()> {
+    CURSOR_HIDDEN.fetch_sub(1, Ordering::SeqCst);
+    w.write_all(SHOW_CURSOR)?;
+    w.flush()
+}
+
+/// Returns the `(columns, rows)` of the controlling terminal, if any.
+///
+/// Queries stderr, stdout and stdin in turn via `TIOCGWINSZ` on Linux, then
+/// falls back to the `COLUMNS` / `LINES` environment variables.
+pub(crate) fn terminal_size() -> Option<(usize, usize)> {
+    ioctl_size().or_else(env_size)
+}
+
+fn env_size() -> Option<(usize, usize)> {
+    let read = |name| std::env::var(name).ok()?.parse::<usize>().ok();
+    let cols = read("COLUMNS").filter(|&c| c > 0)?;
+    Some((cols, read("LINES").unwrap_or(0)))
+}
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    ))
+))]
+fn ioctl_size() -> Option<(usize, usize)> {
+    use std::ffi::{c_int, c_ulong};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        ws_row: u16,
+        ws_col: u16,
+        ws_xpixel: u16,
+        ws_ypixel: u16,
+    }
+
+    const TIOCGWINSZ: c_ulong = 0x5413;
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    [2, 1, 0].into_iter().find_map(|fd| {
+        let mut ws = WinSize::default();
+        // SAFETY: TIOCGWINSZ writes a `struct winsize` through the pointer,
+        // which `WinSize` mirrors exactly.
+        let ok = unsafe { ioctl(fd, TIOCGWINSZ, &mut ws as *mut WinSize) } == 0;
+        (ok && ws.ws_col > 0).then_some((ws.ws_col as usize, ws.ws_row as usize))
+    })
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "sparc64"
+    ))
+)))]
+fn ioctl_size() -> Option<(usize, usize)> {
+    None
+}
@@ -1,10 +1,15 @@
 use std::io::Write;
 
-use crate::{Action, Renderer, TaskId, TaskRenderer};
+use crate::frame::FrameOptions;
+use crate::{
+    Action, Collapse, Color, ColorSupport, FrameWriter, Glyphs, OutputMode, ProgressBar,
+    RenderLoop, Renderer, Spinner, Style, TaskId, TaskRenderer,
+};
 
 pub struct VirtualTerm {
     pub lines: Vec<String>,
     pub cursor_row: usize,
+    cursor_col: usize,
     buf: Vec<u8>,
 }
 
@@ -13,6 +18,7 @@ impl VirtualTerm {
         Self {
             lines: vec![String::new()],
             cursor_row: 0,
+            cursor_col: 0,
             buf: Vec::new(),
         }
     }
@@ -27,33 +33,67 @@ impl VirtualTerm {
         }
     }
 
-    fn process(&mut self, s: &str) {
-        if s.contains("\x1b[") {
-            if let Some(pos) = s.find('A') {
-                let num_str = &s[s.find('[').unwrap() + 1..pos];
-                if let Ok(n) = num_str.parse::<usize>() {
-                    self.cursor_row = self.cursor_row.saturating_sub(n);
-                }
-            }
-            if s.contains("\x1b[2K") {
+    fn put(&mut self, c: char) {
+        self.ensure_row(self.cursor_row);
+        let line = &mut self.lines[self.cursor_row];
+        let mut chars = line.chars().collect::<Vec<_>>();
+        while chars.len() < self.cursor_col {
+            chars.push(' ');
+        }
+        match chars.get_mut(self.cursor_col) {
+            Some(slot) => *slot = c,
+            None => chars.push(c),
+        }
+        *line = chars.into_iter().collect();
+        self.cursor_col += 1;
+    }
+
+    fn csi(&mut self, params: &str, command: char) {
+        let n = params.parse::<usize>().unwrap_or(1);
+        match (command, params) {
+            ('A', _) => self.cursor_row = self.cursor_row.saturating_sub(n),
+            ('B', _) => {
+                self.cursor_row += n;
                 self.ensure_row(self.cursor_row);
-                self.lines[self.cursor_row].clear();
             }
-            if s.contains("\x1b[J") {
+            ('K', "2") => self.lines[self.cursor_row].clear(),
+            ('K', _) => {
+                let line = &mut self.lines[self.cursor_row];
+                *line = line.chars().take(self.cursor_col).collect();
+            }
+            ('J', _) => {
+                let line = &mut self.lines[self.cursor_row];
+                *line = line.chars().take(self.cursor_col).collect();
                 self.lines.truncate(self.cursor_row + 1);
             }
-        } else {
-            for c in s.chars() {
-                match c {
-                    '\n' => {
-                        self.cursor_row += 1;
-                        self.ensure_row(self.cursor_row);
-                    }
-                    _ => {
-                        self.ensure_row(self.cursor_row);
-                        self.lines[self.cursor_row].push(c);
+            _ => {}
+        }
+    }
+
+    fn process(&mut self, s: &str) {
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        match c {
+                            '\x40'..='\x7e' => {
+                                self.csi(&params, c);
+                                break;
+                            }
+                            _ => params.push(c),
+                        }
                     }
                 }
+                '\r' => self.cursor_col = 0,
+                '\n' => {
+                    self.cursor_row += 1;
+                    self.cursor_col = 0;
+                    self.ensure_row(self.cursor_row);
+                }
+                _ => self.put(c),
             }
         }
     }
@@ -98,20 +138,68 @@ impl Renderer for TestRenderer {
         let indent = " ".repeat(task.depth());
         writeln!(target, "{}{}", indent, task.data())
     }
+
+    fn summary_label(&self, task: &crate::TaskView<'_, Self>) -> Option<String> {
+        Some(task.data().clone())
+    }
+
+    fn error_message(&self, event: &crate::EventView<'_, Self>) -> Option<String> {
+        event.data().strip_prefix("error: ").map(str::to_string)
+    }
 }
 
-struct TestEnv {
+/// Like [`TestRenderer`], but marks finished tasks, so append-mode output
+/// shows the state each task line was rendered in.
+#[derive(Default)]
+struct StateRenderer;
+
+impl Renderer for StateRenderer {
+    type EventData = String;
+    type TaskData = String;
+
+    fn render_task_line(
+        &mut self,
+        target: &mut crate::FrameWriter<'_>,
+        task: &crate::TaskView<'_, Self>,
+    ) -> Result<(), std::io::Error> {
+        let indent = " ".repeat(task.depth());
+        let state = match (task.completed(), task.cancelled()) {
+            (true, _) => " (done)",
+            (_, true) => " (cancelled)",
+            _ => "",
+        };
+        writeln!(target, "{}{}{}", indent, task.data(), state)
+    }
+
+    fn render_event_line(
+        &mut self,
+        target: &mut crate::FrameWriter<'_>,
+        task: &crate::EventView<'_, Self>,
+    ) -> Result<(), std::io::Error> {
+        let indent = " ".repeat(task.depth());
+        writeln!(target, "{}{}", indent, task.data())
+    }
+}
+
+struct TestEnv<R: Renderer = TestRenderer> {
     term: VirtualTerm,
-    writer: TaskRenderer<TestRenderer>,
+    writer: TaskRenderer<R>,
     task: Option<TaskId>,
     counter: usize,
 }
 
 impl TestEnv {
     pub fn new() -> Self {
+        // Redraws in place by default, whatever stderr is.
+        Self::with_writer(TaskRenderer::new(TestRenderer).glyphs(Glyphs::UNICODE))
+    }
+}
+
+impl<R: Renderer<TaskData = String, EventData = String>> TestEnv<R> {
+    fn with_writer(writer: TaskRenderer<R>) -> Self {
         Self {
             term: VirtualTerm::new(),
-            writer: TaskRenderer::new(TestRenderer),
+            writer,
             task: None,
             counter: 1,
         }
@@ -146,6 +234,26 @@ impl TestEnv {
     }
 }
 
+/// Starts task `id` under `parent`, for tests that build a tree by hand.
+fn start<R: Renderer>(id: usize, parent: Option<usize>, name: &str) -> Action<R>
+where
+    for<'a> R::TaskData: From<&'a str>,
+{
+    Action::TaskStart {
+        id: TaskId::from(id),
+        parent: parent.map(TaskId::from),
+        data: name.into(),
+    }
+}
+
+/// Frame options for a terminal `width` columns wide.
+fn sized(width: usize) -> FrameOptions {
+    FrameOptions {
+        width: Some(width),
+        ..FrameOptions::default()
+    }
+}
+
 #[test]
 fn test_virtual_term() {
     let mut env = TestEnv::new();
@@ -205,3 +313,1034 @@ fn test_event_overflow() {
         assert_eq!(env.render(), " s\n e2\n e3\n e4\n");
     });
 }
+
+#[test]
+fn test_no_buffered_events() {
+    let mut env = TestEnv::new();
+    env.writer = TaskRenderer::new(TestRenderer).max_events_per_task(0);
+    env.span("s", |env| {
+        env.event("e1");
+        assert_eq!(env.render(), " s\n");
+    });
+}
+
+#[test]
+fn test_suspend_prints_above_frame() {
+    let mut env = TestEnv::new();
+    env.span("s", |env| {
+        env.render();
+        env.writer
+            .suspend(&mut env.term, |w| writeln!(w, "hello"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(env.term.render(), "hello\n s\n");
+    });
+}
+
+#[test]
+fn test_frame_clipped_to_height() {
+    let mut env = TestEnv::new();
+    env.writer = TaskRenderer::new(TestRenderer)
+        .mode(OutputMode::Interactive)
+        .glyphs(Glyphs::UNICODE)
+        .max_height(5);
+    env.writer.update(start(1, None, "a"));
+    for (id, name) in [(2, "c1"), (3, "c2"), (4, "c3"), (5, "c4"), (6, "c5")] {
+        env.writer.update(start(id, Some(1), name));
+    }
+    env.writer.update(start(7, Some(6), "deep"));
+    assert_eq!(env.render(), " a\n  c5\n   deep\n… and 4 more tasks\n");
+}
+
+#[test]
+fn test_completed_subtree_collapses_to_summary() {
+    struct Collapsing;
+
+    impl Renderer for Collapsing {
+        type EventData = String;
+        type TaskData = String;
+
+        fn collapse(&self, _task: &crate::TaskView<'_, Self>) -> Collapse {
+            Collapse::OnSuccess
+        }
+
+        fn render_task_line(
+            &mut self,
+            f: &mut FrameWriter<'_>,
+            task: &crate::TaskView<'_, Self>,
+        ) -> std::io::Result<()> {
+            writeln!(f, "{}{}", " ".repeat(task.depth()), task.data())
+        }
+    }
+
+    let mut writer = TaskRenderer::new(Collapsing).mode(OutputMode::Interactive);
+    let mut term = VirtualTerm::new();
+    writer.update(start(1, None, "root"));
+    writer.update(start(2, Some(1), "tests"));
+    writer.update(start(3, Some(2), "a"));
+    writer.update(start(4, Some(2), "b"));
+    writer.update(start(5, Some(1), "lint"));
+    writer.render(&mut term).unwrap();
+    assert_eq!(term.render(), " root\n  tests\n   a\n   b\n  lint\n");
+
+    for id in [3, 4, 2] {
+        writer.update(Action::TaskEnd {
+            id: TaskId::from(id),
+        });
+    }
+    writer.render(&mut term).unwrap();
+    let lines = term.lines.clone();
+    assert_eq!(lines[0], " root");
+    assert!(lines[1].starts_with("  tests (2 done) 0."), "{lines:?}");
+    assert_eq!(lines[2..], ["  lint", ""]);
+}
+
+#[test]
+fn test_tree_preset() {
+    use crate::presets::{Level, TreeRenderer};
+    use crate::widgets::Spinner;
+
+    let render = |glyphs: Glyphs| {
+        let preset = TreeRenderer::new()
+            .spinner(Spinner::custom(&["*"]))
+            .elapsed(false)
+            .events(1);
+        let mut writer = TaskRenderer::new(preset)
+            .mode(OutputMode::Interactive)
+            .color(ColorSupport::None)
+            .glyphs(glyphs);
+        let mut term = VirtualTerm::new();
+        writer.update(start(1, None, "deploy"));
+        writer.update(Action::Event {
+            parent: Some(TaskId::from(1)),
+            data: "old".into(),
+        });
+        writer.update(Action::Event {
+            parent: Some(TaskId::from(1)),
+            data: crate::presets::EventInfo::new(Level::Warn, "slow"),
+        });
+        writer.update(start(2, Some(1), "build"));
+        writer.update(Action::TaskEnd {
+            id: TaskId::from(2),
+        });
+        writer.update(start(3, Some(1), "upload"));
+        writer.update(Action::Event {
+            parent: Some(TaskId::from(3)),
+            data: "3/40".into(),
+        });
+        writer.update(Action::EventsSuppressed {
+            parent: Some(TaskId::from(3)),
+            count: 4,
+        });
+        writer.update(Action::EventsSuppressed {
+            parent: None,
+            count: 1,
+        });
+        writer.render(&mut term).unwrap();
+        // The root's count goes to the scrollback once.
+        writer.render(&mut term).unwrap();
+        term.render()
+    };
+    assert_eq!(
+        render(Glyphs::UNICODE),
+        "… suppressed 1 event\n* deploy\n│ slow\n├── ✔ build\n└── * upload\n      3/40\n      … suppressed 4 events\n"
+    );
+    assert_eq!(
+        render(Glyphs::ASCII),
+        "~ suppressed 1 event\n* deploy\n| slow\n|-- + build\n`-- * upload\n      3/40\n      ~ suppressed 4 events\n"
+    );
+}
+
+#[test]
+fn test_render_lines_offscreen() {
+    use crate::presets::TreeRenderer;
+    use crate::widgets::Spinner;
+
+    let preset = TreeRenderer::new()
+        .spinner(Spinner::custom(&["*"]))
+        .elapsed(false);
+    let mut writer = TaskRenderer::new(preset).glyphs(Glyphs::UNICODE);
+    writer.update(start(1, None, "build"));
+    writer.update(Action::TaskEnd {
+        id: TaskId::from(1),
+    });
+    writer.update(start(2, None, "deploy"));
+    writer.update(start(3, Some(2), "upload a very long file name"));
+
+    // Finished tasks stay in the tree across renders.
+    for _ in 0..2 {
+        let lines = writer.render_lines(20, 10).unwrap();
+        let text = lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>();
+        assert_eq!(text, ["✔ build", "* deploy", "└── * upload a very…"]);
+        assert_eq!(
+            lines[0].styled().collect::<Vec<_>>(),
+            [
+                ("✔", Style::new().green()),
+                (" ", Style::new()),
+                ("build", Style::new().bold())
+            ]
+        );
+    }
+
+    let lines = writer.render_lines(20, 2).unwrap();
+    let text = lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(text, ["* deploy", "… and 2 more tasks"]);
+}
+
+#[test]
+fn test_commit_depth_moves_nested_tasks_to_scrollback() {
+    let mut env = TestEnv::new();
+    env.writer = TaskRenderer::new(TestRenderer)
+        .mode(OutputMode::Interactive)
+        .commit_depth(2);
+    env.span("main", |env| {
+        env.span("a", |env| env.event("x"));
+        assert_eq!(env.render(), "  a\n main\n");
+        env.span("b", |env| {
+            env.span("b1", |_| {});
+            assert_eq!(env.render(), "  a\n main\n  b\n   b1\n");
+        });
+        assert_eq!(env.render(), "  a\n  b\n   b1\n main\n");
+    });
+}
+
+#[test]
+fn test_render_loop_commit_when() {
+    let (tx, mut rx) = std::sync::mpsc::channel::<Action<TestRenderer>>();
+    for action in [
+        start(1, None, "main"),
+        start(2, Some(1), "a"),
+        start(3, Some(1), "b"),
+    ] {
+        tx.send(action).unwrap();
+    }
+    for id in [2, 3] {
+        tx.send(Action::TaskEnd {
+            id: TaskId::from(id),
+        })
+        .unwrap();
+    }
+    let out = SharedBuf::default();
+    let mut render_loop = RenderLoop::new(TestRenderer, out.clone())
+        .mode(OutputMode::Interactive)
+        .commit_when(|task| task.data() == "a");
+    render_loop.tick(&mut rx);
+    let out = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+    // `a` goes to the scrollback above the frame, `b` stays in it.
+    let at = |line: &str| out.find(line).unwrap();
+    assert!(at("  a\n") < at(" main\n"));
+    assert!(at(" main\n") < at("  b\n"));
+}
+
+#[test]
+fn test_append_mode_prints_once_in_order() {
+    let mut env = TestEnv::with_writer(TaskRenderer::new(StateRenderer).mode(OutputMode::Append));
+    env.span("outer", |env| {
+        env.event("a");
+        env.render();
+        env.span("inner", |env| env.event("b"));
+        env.render();
+    });
+    env.event("after");
+    // `inner` starts and ends between renders, yet its start line is active.
+    assert_eq!(
+        env.render(),
+        " outer\n a\n  inner\n  b\n  inner (done)\n outer (done)\nafter\n"
+    );
+
+    // Events beyond the per-task buffer between renders are still printed.
+    let mut env = TestEnv::new();
+    env.writer = TaskRenderer::new(TestRenderer).mode(OutputMode::Append);
+    for i in 0..100 {
+        env.event(&format!("e{i}"));
+    }
+    let expected = (0..100).map(|i| format!("e{i}\n")).collect::<String>();
+    assert_eq!(env.render(), expected);
+}
+
+#[test]
+fn test_redraw_only_changed_lines() {
+    let mut env = TestEnv::new();
+    env.writer = TaskRenderer::new(TestRenderer)
+        .mode(OutputMode::Interactive)
+        .width(80);
+    env.span("s", |env| {
+        env.event("a");
+        env.render();
+
+        let mut out = Vec::new();
+        env.event("b");
+        env.writer.render(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?2026h b\n\x1b[?2026l"
+        );
+
+        let mut out = Vec::new();
+        env.event("c");
+        env.event("d");
+        env.writer.render(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?2026h\r\x1b[2A\x1b[2K b\n\x1b[2K c\n d\n\x1b[?2026l"
+        );
+    });
+}
+
+#[derive(Clone, Default)]
+struct SharedBuf(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_failed_frame_ends_synchronized_update() {
+    /// Fails the first write after a synchronized update begins.
+    #[derive(Default)]
+    struct Flaky {
+        out: Vec<u8>,
+        fail_next: bool,
+    }
+
+    impl std::io::Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if std::mem::take(&mut self.fail_next) {
+                return Err(std::io::ErrorKind::BrokenPipe.into());
+            }
+            self.fail_next = buf == crate::term::SYNC_BEGIN;
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut env = TestEnv::new();
+    env.event("e");
+    let mut target = Flaky::default();
+    assert!(env.writer.render(&mut target).is_err());
+    assert!(target.out.ends_with(crate::term::SYNC_END));
+}
+
+#[test]
+fn test_render_loop_restores_cursor() {
+    let out = SharedBuf::default();
+    let (tx, rx) = std::sync::mpsc::channel::<Action<TestRenderer>>();
+    drop(tx);
+    RenderLoop::new(TestRenderer, out.clone())
+        .mode(OutputMode::Interactive)
+        .run(rx);
+    let out = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+    assert!(out.starts_with("\x1b[?25l"));
+    assert!(out.ends_with("\x1b[?25h"));
+
+    let out = SharedBuf::default();
+    let mut render_loop = RenderLoop::new(TestRenderer, out.clone()).mode(OutputMode::Interactive);
+    render_loop.tick(&mut std::sync::mpsc::channel().1);
+    drop(render_loop);
+    let out = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+    assert!(out.ends_with("\x1b[?25h"));
+}
+
+#[test]
+fn test_render_handle_defer_returns_before_the_tick() {
+    let out = SharedBuf::default();
+    let mut render_loop = RenderLoop::new(TestRenderer, out.clone()).mode(OutputMode::Append);
+    let handle = render_loop.handle();
+    assert!(handle.defer(|w| writeln!(w, "hello").unwrap()));
+    assert!(out.0.lock().unwrap().is_empty());
+    render_loop.tick(&mut std::sync::mpsc::channel().1);
+    assert_eq!(out.0.lock().unwrap().as_slice(), b"hello\n");
+
+    drop(render_loop);
+    assert!(!handle.defer(|_| {}));
+}
+
+#[test]
+fn test_render_loop_returns_run_summary() {
+    let (tx, rx) = std::sync::mpsc::channel::<Action<TestRenderer>>();
+    let event = |parent: usize, data: &str| Action::Event {
+        parent: Some(TaskId::from(parent)),
+        data: data.to_string(),
+    };
+    let actions = [
+        start(1, None, "main"),
+        start(2, Some(1), "fetch"),
+        event(2, "error: timed out"),
+        Action::TaskEnd {
+            id: TaskId::from(2),
+        },
+        start(3, Some(1), "build"),
+        event(3, "ok"),
+        Action::TaskEnd {
+            id: TaskId::from(3),
+        },
+        start(4, Some(1), "deploy"),
+    ];
+    for action in actions {
+        tx.send(action).unwrap();
+    }
+    drop(tx);
+
+    let summary = RenderLoop::new(TestRenderer, SharedBuf::default())
+        .mode(OutputMode::Append)
+        .run(rx);
+    assert_eq!(
+        (
+            summary.started,
+            summary.completed,
+            summary.failed,
+            summary.cancelled
+        ),
+        (4, 1, 1, 2)
+    );
+    assert_eq!(summary.errors, ["timed out"]);
+    let mut slowest = summary
+        .slowest
+        .iter()
+        .map(|(l, _)| l.as_str())
+        .collect::<Vec<_>>();
+    slowest.sort();
+    assert_eq!(slowest, ["build", "fetch"]);
+    assert!(!summary.success());
+}
+
+#[test]
+fn test_style_resolves_against_color_support() {
+    let style = Style::new().fg(Color::Rgb(255, 0, 0)).bold();
+    let paint = |support| style.paint("x", support).to_string();
+    assert_eq!(
+        paint(ColorSupport::TrueColor),
+        "\x1b[1;38;2;255;0;0mx\x1b[0m"
+    );
+    assert_eq!(paint(ColorSupport::Ansi256), "\x1b[1;38;5;196mx\x1b[0m");
+    assert_eq!(paint(ColorSupport::Ansi16), "\x1b[1;91mx\x1b[0m");
+    assert_eq!(paint(ColorSupport::None), "x");
+
+    // Greys map onto the grey ramp, and the lightest onto white.
+    let grey = |v| {
+        let style = Style::new().fg(Color::Rgb(v, v, v));
+        style.paint("x", ColorSupport::Ansi256).to_string()
+    };
+    assert_eq!(grey(247), "\x1b[38;5;255mx\x1b[0m");
+    assert_eq!(grey(248), "\x1b[38;5;231mx\x1b[0m");
+    assert_eq!(grey(255), "\x1b[38;5;231mx\x1b[0m");
+
+    let mut out = Vec::new();
+    let options = FrameOptions {
+        color: Some(ColorSupport::Ansi16),
+        ..FrameOptions::default()
+    };
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    write!(f, "{}", f.style(Style::new().green(), "ok")).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "\x1b[32mok\x1b[0m");
+}
+
+#[test]
+fn test_frame_counts_wrapped_rows() {
+    let mut out = Vec::new();
+    let options = sized(10);
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    writeln!(f, "\x1b[32m{}\x1b[0m", "x".repeat(25)).unwrap();
+    writeln!(f, "{}", "y".repeat(10)).unwrap();
+    assert_eq!(f.frame_lines(), 4);
+}
+
+#[test]
+fn test_frame_truncates_to_width() {
+    let mut out = Vec::new();
+    let options = FrameOptions {
+        truncate: true,
+        ..sized(10)
+    };
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    writeln!(f, "\x1b[1mabcdefghijkl\x1b[0m").unwrap();
+    writeln!(f, "short").unwrap();
+    assert_eq!(f.frame_lines(), 2);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\x1b[1mabcdefghi…\x1b[0m\nshort\n"
+    );
+}
+
+#[test]
+fn test_frame_measures_wide_characters() {
+    let mut out = Vec::new();
+    let options = FrameOptions {
+        truncate: true,
+        ..sized(5)
+    };
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    writeln!(f, "日本語.rs").unwrap();
+    writeln!(f, "e\u{301}e\u{301}e\u{301}").unwrap();
+    assert_eq!(f.frame_lines(), 2);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "日本…\ne\u{301}e\u{301}e\u{301}\n"
+    );
+
+    // A wide char that doesn't fit in the last column wraps as a whole.
+    let mut out = Vec::new();
+    let options = sized(5);
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    writeln!(f, "日本語").unwrap();
+    assert_eq!(f.frame_lines(), 2);
+
+    let spinner = Spinner::custom(&["🌑", "*"]);
+    assert_eq!(format!("{spinner}|"), "🌑|");
+    let bar = ProgressBar::new(1, 2).width(5).chars('█', '・');
+    assert_eq!(bar.to_string(), "[██・ ]  50%");
+}
+
+#[test]
+fn test_resize_clears_rewrapped_frame() {
+    #[derive(Default)]
+    struct Resizing(std::sync::Arc<std::sync::Mutex<Vec<usize>>>);
+
+    impl Renderer for Resizing {
+        type EventData = String;
+        type TaskData = String;
+
+        fn on_resize(&mut self, width: usize, _height: Option<usize>) {
+            self.0.lock().unwrap().push(width);
+        }
+
+        fn render_task_line(
+            &mut self,
+            f: &mut FrameWriter<'_>,
+            task: &crate::TaskView<'_, Self>,
+        ) -> std::io::Result<()> {
+            writeln!(f, "{} {}", task.data(), f.width())
+        }
+
+        fn render_event_line(
+            &mut self,
+            _f: &mut FrameWriter<'_>,
+            _event: &crate::EventView<'_, Self>,
+        ) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let sizes = Resizing::default();
+    let seen = sizes.0.clone();
+    let mut writer = TaskRenderer::new(sizes)
+        .mode(OutputMode::Interactive)
+        .synchronized_output(false)
+        .max_height(10)
+        .width(10);
+    writer.update(Action::TaskStart {
+        id: TaskId::from(1),
+        parent: None,
+        data: "compile".into(),
+    });
+    let mut out = Vec::new();
+    writer.render(&mut out).unwrap();
+    writer.render(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "compile 10\n");
+
+    // At 5 columns the 10-column line has rewrapped onto two rows.
+    let mut writer = writer.width(5);
+    let mut out = Vec::new();
+    writer.render(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\r\x1b[2A\x1b[2K\x1b[Jcompile 5\n"
+    );
+    assert_eq!(*seen.lock().unwrap(), [10, 5]);
+}
+
+#[test]
+fn test_human_duration_formats() {
+    use crate::HumanDuration;
+    use std::time::Duration;
+
+    let cases = [
+        (Duration::from_millis(4_120), ["4.1s", "00:04", "4.120s"]),
+        (
+            Duration::from_millis(83_456),
+            ["1m 23s", "01:23", "1m 23.456s"],
+        ),
+        (
+            Duration::from_secs(3_723),
+            ["1h 2m", "1:02:03", "1h 02m 03.000s"],
+        ),
+    ];
+    for (duration, [compact, clock, precise]) in cases {
+        let human = HumanDuration::new(duration);
+        assert_eq!(human.to_string(), compact);
+        assert_eq!(human.clock().to_string(), clock);
+        assert_eq!(human.precise().to_string(), precise);
+    }
+    let fixed = |ms| HumanDuration::new(Duration::from_millis(ms)).fixed();
+    assert_eq!(fixed(4_120).to_string(), "   4.1s");
+    assert_eq!(fixed(65_000).to_string(), " 1m 05s");
+    assert_eq!(fixed(83_456).clock().to_string(), "00:01:23");
+    assert_eq!(fixed(4_120).precise().to_string(), "     4.120s");
+}
+
+#[test]
+fn test_bytes_and_count_formats() {
+    use crate::{Bytes, Count};
+
+    let bytes = |n| Bytes::new(n).to_string();
+    assert_eq!(bytes(512), "512 B");
+    assert_eq!(bytes(1023), "1023 B");
+    assert_eq!(bytes(1024), "1.0 KiB");
+    assert_eq!(bytes(12_998_144), "12.4 MiB");
+    assert_eq!(bytes(471_859_200), "450 MiB");
+    assert_eq!(bytes(1024 * 1024 - 1), "1.0 MiB");
+    assert_eq!(Bytes::new(12_998_144).si().to_string(), "13.0 MB");
+    assert_eq!(Bytes::new(12_998_144).precision(2).to_string(), "12.40 MiB");
+
+    assert_eq!(Count::new(0).to_string(), "0");
+    assert_eq!(Count::new(999).to_string(), "999");
+    assert_eq!(Count::new(1_234_567).to_string(), "1,234,567");
+    let compact = |n| Count::new(n).compact().to_string();
+    assert_eq!(compact(999), "999");
+    assert_eq!(compact(3_400), "3.4k");
+    assert_eq!(compact(999_999), "1.0M");
+    assert_eq!(compact(1_234_567), "1.2M");
+
+    let bar = ProgressBar::new(471_859_200, 1 << 30)
+        .width(6)
+        .chars('#', '-')
+        .label(Bytes::new);
+    assert_eq!(bar.to_string(), "[##----]  44% 450 MiB / 1.0 GiB");
+}
+
+#[test]
+fn test_sparkline_scales_samples() {
+    use crate::glyphs::GlyphScope;
+    use crate::task::TaskStore;
+    use crate::{Sparkline, TaskView};
+    use std::time::Duration;
+
+    let _glyphs = GlyphScope::enter(Glyphs::UNICODE);
+    let mut sparkline = Sparkline::new(8);
+    assert_eq!(sparkline.to_string(), " ".repeat(8));
+    for value in [1.0, 2.0, 5.0, 7.0] {
+        sparkline.push(value);
+    }
+    assert_eq!(sparkline.to_string(), "    ▂▃▆█");
+    assert_eq!(sparkline.clone().max(14.0).to_string(), "    ▂▂▄▅");
+    for value in [6.0, 3.0, 0.0, -1.0, 7.0] {
+        sparkline.push(value);
+    }
+    assert_eq!(sparkline.samples().len(), 8);
+    assert_eq!(sparkline.to_string(), "▃▆█▇▄▁▁█");
+
+    let mut tasks = TaskStore::<TestRenderer>::new();
+    let id = TaskId::new(1.try_into().unwrap());
+    tasks.apply(Action::TaskStart {
+        id,
+        parent: None,
+        data: "worker".to_string(),
+    });
+    for _ in 0..3 {
+        tasks.apply(Action::Event {
+            parent: Some(id),
+            data: "job".to_string(),
+        });
+    }
+    let task = TaskView::new(&tasks, id);
+    let activity = Sparkline::event_rate(&task, 4, Duration::from_secs(3600));
+    assert_eq!(activity.samples().collect::<Vec<_>>(), [0.0, 0.0, 0.0, 3.0]);
+    assert_eq!(activity.to_string(), "▁▁▁█");
+
+    let _glyphs = GlyphScope::enter(Glyphs::ASCII);
+    assert_eq!(activity.to_string(), "___#");
+}
+
+#[test]
+fn test_progress_bar_modes() {
+    use crate::glyphs::GlyphScope;
+    use crate::style::ColorScope;
+    use crate::{BarSuffix, Count};
+
+    let _glyphs = GlyphScope::enter(Glyphs::UNICODE);
+    let bar = |done, total| ProgressBar::new(done, total);
+    assert_eq!(
+        bar(45, 100).width(30).to_string(),
+        "[█████████████▌░░░░░░░░░░░░░░░░]  45%"
+    );
+    assert_eq!(
+        bar(46, 100).suffix(BarSuffix::Fraction).to_string(),
+        "[█████████▏░░░░░░░░░░] 46/100"
+    );
+    assert_eq!(
+        bar(1, 1).width(4).suffix(BarSuffix::None).to_string(),
+        "[████]"
+    );
+    assert_eq!(bar(0, 0).width(8).to_string(), "[░░░░░░░░]   0%");
+
+    // The block bounces between the ends, ignoring the suffix.
+    let pulse = |tick| bar(1234, 0).width(8).pulse(tick).label(Count::new);
+    assert_eq!(pulse(0).to_string(), "[██░░░░░░] 1,234");
+    assert_eq!(pulse(3).to_string(), "[░░░██░░░] 1,234");
+    assert_eq!(pulse(6).to_string(), "[░░░░░░██] 1,234");
+    assert_eq!(pulse(8).to_string(), "[░░░░██░░] 1,234");
+    assert_eq!(bar(1, 2).width(4).pulse(3).to_string(), "[██░░]  50%");
+
+    // Styles only apply where the color support allows.
+    let styled = bar(1, 2)
+        .width(2)
+        .filled_style(Style::new().green())
+        .empty_style(Style::new().gray());
+    assert_eq!(styled.to_string(), "[█░]  50%");
+    let _color = ColorScope::enter(ColorSupport::Ansi16);
+    assert_eq!(
+        styled.to_string(),
+        "[\x1b[32m█\x1b[0m\x1b[90m░\x1b[0m]  50%"
+    );
+
+    // Wide characters fill whole cells, leaving no partial cell.
+    let wide = bar(3, 10).width(10).chars('🟩', '⬜');
+    assert_eq!(wide.to_string(), "[🟩⬜⬜⬜⬜]  30%");
+
+    let _glyphs = GlyphScope::enter(Glyphs::ASCII);
+    assert_eq!(bar(45, 100).width(10).to_string(), "[####------]  45%");
+}
+
+#[test]
+fn test_rate_estimator_smooths_and_stalls() {
+    use crate::RateEstimator;
+    use std::time::{Duration, Instant};
+
+    const MIB: u64 = 1024 * 1024;
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let mut rate = RateEstimator::new();
+    assert_eq!(rate.rate().to_string(), "-/s");
+    assert_eq!(rate.eta(Some(43 * MIB)).to_string(), "-:--");
+
+    for secs in 0..=10 {
+        rate.update_at(secs * MIB, at(secs));
+    }
+    assert_eq!(rate.rate().bytes().to_string(), "1.0 MiB/s");
+    assert_eq!(rate.rate().unit("B").to_string(), "1048576 B/s");
+    assert_eq!(rate.eta(Some(43 * MIB)).to_string(), "0:33");
+    assert_eq!(rate.eta(None).to_string(), "-:--");
+
+    // A burst moves the estimate part of the way, not all of it.
+    rate.update_at(20 * MIB, at(11));
+    let per_sec = rate.per_sec().unwrap() / MIB as f64;
+    assert!(per_sec > 1.0 && per_sec < 10.0, "{per_sec}");
+
+    rate.update_at(20 * MIB, at(16));
+    assert!(rate.is_stalled());
+    assert_eq!(rate.rate().bytes().to_string(), "stalled");
+    assert_eq!(rate.eta(Some(43 * MIB)).to_string(), "stalled");
+
+    // A counter that goes backwards starts over.
+    rate.update_at(0, at(17));
+    assert!(!rate.is_stalled());
+    assert_eq!(rate.per_sec(), None);
+
+    // A finished counter stops moving without stalling.
+    let mut rate = RateEstimator::new();
+    rate.update_at(0, at(0));
+    rate.update_at(10, at(1));
+    assert_eq!(rate.eta(Some(10)).to_string(), "0:00");
+    rate.update_at(10, at(30));
+    assert_eq!(rate.eta(Some(10)).to_string(), "0:00");
+    assert_eq!(rate.rate().to_string(), "stalled");
+    rate.set_total(Some(10));
+    assert!(!rate.is_stalled());
+    assert_eq!(rate.rate().to_string(), "0.0/s");
+}
+
+#[test]
+fn test_line_builder_columns() {
+    let mut out = Vec::new();
+    let options = sized(20);
+    let mut f = FrameWriter::new(&mut out, 0, options);
+    f.line()
+        .left("> ")
+        .fill("build")
+        .right("1.2s")
+        .finish()
+        .unwrap();
+    f.line()
+        .left("> ")
+        .fill("a rather long task name")
+        .right("1.2s")
+        .finish()
+        .unwrap();
+    f.line()
+        .fill("name")
+        .max_width(8)
+        .right("ok")
+        .finish()
+        .unwrap();
+    f.line()
+        .left("a")
+        .center("mid")
+        .right("b")
+        .finish()
+        .unwrap();
+    f.line().left("id").min_width(4).fill("x").finish().unwrap();
+    assert_eq!(f.frame_lines(), 5);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "> build         1.2s\n\
+         > a rather long…1.2s\n\
+         name              ok\n\
+         a       mid        b\n\
+         id  x\n"
+    );
+}
+
+#[cfg(feature = "tracing")]
+mod layer {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::TestRenderer;
+    use crate::tracing::{ActionHandler, TaskLayer};
+    use crate::{Action, TraceMapper};
+
+    struct NameMapper;
+
+    impl TraceMapper for NameMapper {
+        type EventData = String;
+        type TaskData = String;
+
+        fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
+            match attrs.metadata().name() {
+                "hidden" => None,
+                name => Some(name.to_string()),
+            }
+        }
+
+        fn map_event(event: &tracing::Event<'_>) -> Option<String> {
+            let mut message = String::new();
+            event.record(
+                &mut |field: &tracing::field::Field, value: &dyn std::fmt::Debug| {
+                    if field.name() == "message" {
+                        message = format!("{value:?}");
+                    }
+                },
+            );
+            match message.as_str() {
+                "skip" => None,
+                _ => Some(message),
+            }
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Action<TestRenderer>>>>);
+
+    impl ActionHandler<TestRenderer> for Recorder {
+        fn handle(&self, action: Action<TestRenderer>) {
+            self.0.lock().unwrap().push(action);
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_suppresses_excess_events() {
+        let recorder = Recorder::default();
+        let layer = TaskLayer::<NameMapper, TestRenderer, _>::new(recorder.clone())
+            .rate_limit(2, Duration::from_secs(3600));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("hot").in_scope(|| {
+                for _ in 0..5 {
+                    tracing::info!("item");
+                }
+            });
+        });
+
+        let actions = recorder.0.lock().unwrap();
+        let events = actions
+            .iter()
+            .filter(|a| matches!(a, Action::Event { .. }))
+            .count();
+        assert_eq!(events, 2);
+        assert!(matches!(
+            actions[actions.len() - 2],
+            Action::EventsSuppressed { count: 3, .. }
+        ));
+        assert!(matches!(actions.last(), Some(Action::TaskEnd { .. })));
+    }
+
+    #[test]
+    fn test_rate_limit_flushes_root_count() {
+        let recorder = Recorder::default();
+        let layer = TaskLayer::<NameMapper, TestRenderer, _>::new(recorder.clone())
+            .rate_limit(1, Duration::from_millis(10));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..3 {
+                tracing::info!("item");
+            }
+            std::thread::sleep(Duration::from_millis(20));
+            // No further root event arrives; the next span reports the count.
+            tracing::info_span!("next").in_scope(|| {});
+        });
+
+        let actions = recorder.0.lock().unwrap();
+        assert!(matches!(
+            actions[1],
+            Action::EventsSuppressed {
+                parent: None,
+                count: 2
+            }
+        ));
+        assert!(matches!(actions[2], Action::TaskStart { .. }));
+    }
+
+    #[test]
+    fn test_unmapped_spans_are_transparent() {
+        let recorder = Recorder::default();
+        let layer = TaskLayer::<NameMapper, TestRenderer, _>::new(recorder.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("outer").in_scope(|| {
+                tracing::info_span!("hidden").in_scope(|| {
+                    tracing::info!("skip");
+                    tracing::info!("kept");
+                    tracing::info_span!("inner").in_scope(|| {});
+                });
+            });
+        });
+
+        let actions = recorder.0.lock().unwrap();
+        let Action::TaskStart { id: outer, .. } = actions[0] else {
+            panic!("expected outer TaskStart");
+        };
+        assert!(matches!(
+            &actions[1],
+            Action::Event { parent: Some(p), data } if *p == outer && data == "kept"
+        ));
+        assert!(matches!(
+            &actions[2],
+            Action::TaskStart { parent: Some(p), data, .. } if *p == outer && data == "inner"
+        ));
+        assert_eq!(actions.len(), 5);
+    }
+
+    struct AllMapper;
+
+    impl TraceMapper for AllMapper {
+        type EventData = String;
+        type TaskData = String;
+
+        fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
+            Some(attrs.metadata().name().to_string())
+        }
+
+        fn map_event(_: &tracing::Event<'_>) -> Option<String> {
+            Some(String::new())
+        }
+    }
+
+    #[test]
+    fn test_layers_do_not_share_task_ids() {
+        let all = Recorder::default();
+        let named = Recorder::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(TaskLayer::<AllMapper, TestRenderer, _>::new(all.clone()))
+            .with(TaskLayer::<NameMapper, TestRenderer, _>::new(named.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("hidden").in_scope(|| {
+                tracing::info_span!("inner").in_scope(|| {});
+            });
+        });
+
+        let all = all.0.lock().unwrap();
+        let Action::TaskStart { id: outer, .. } = &all[0] else {
+            panic!("expected the outer span to start first");
+        };
+        assert!(matches!(
+            &all[1],
+            Action::TaskStart { parent: Some(parent), .. } if parent == outer
+        ));
+        let named = named.0.lock().unwrap();
+        assert!(matches!(&named[0], Action::TaskStart { parent: None, .. }));
+        assert!(matches!(named.last(), Some(Action::TaskEnd { .. })));
+        assert_eq!(named.len(), 2);
+    }
+}
+
+#[cfg(feature = "ratatui")]
+mod ratatui {
+    use ratatui_core::buffer::Buffer;
+    use ratatui_core::layout::Rect;
+    use ratatui_core::style::{Color, Modifier};
+    use ratatui_core::widgets::{StatefulWidget, Widget};
+
+    use super::TestRenderer;
+    use crate::{Action, Glyphs, TaskId, TaskRenderer, TaskTreeState, TaskTreeWidget};
+
+    fn tasks(names: &[&str]) -> TaskRenderer<TestRenderer> {
+        let mut writer = TaskRenderer::new(TestRenderer).glyphs(Glyphs::UNICODE);
+        for (i, name) in names.iter().enumerate() {
+            writer.update(Action::TaskStart {
+                id: TaskId::from(i + 1),
+                parent: None,
+                data: name.to_string(),
+            });
+        }
+        writer
+    }
+
+    #[test]
+    fn test_task_tree_widget_draws_into_buffer() {
+        let mut writer = tasks(&["build", "a long test name"]);
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buf = Buffer::empty(area);
+        Widget::render(TaskTreeWidget::new(&mut writer), area, &mut buf);
+        assert_eq!(
+            buf,
+            Buffer::with_lines([" build    ", " a long t…", "          "])
+        );
+    }
+
+    #[test]
+    fn test_task_tree_state_scrolls_to_selection() {
+        let mut writer = tasks(&["a", "b", "c", "d"]);
+        let area = Rect::new(0, 0, 4, 2);
+        let mut state = TaskTreeState::default();
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(TaskTreeWidget::new(&mut writer), area, &mut buf, &mut state);
+        for _ in 0..5 {
+            state.select_next();
+        }
+        let mut buf = Buffer::empty(area);
+        let widget = TaskTreeWidget::new(&mut writer).highlight_style(Color::Red);
+        StatefulWidget::render(widget, area, &mut buf, &mut state);
+        assert_eq!((state.offset(), state.selected()), (2, Some(3)));
+        let mut expected = Buffer::with_lines([" c  ", " d  "]);
+        expected.set_style(Rect::new(0, 1, 4, 1), Color::Red);
+        assert_eq!(buf, expected);
+        assert!(!buf[(0, 0)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_task_tree_state_ignores_empty_area() {
+        let mut writer = tasks(&["a", "b"]);
+        let mut state = TaskTreeState::default();
+        state.select(Some(1));
+        let area = Rect::new(0, 0, 4, 0);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(TaskTreeWidget::new(&mut writer), area, &mut buf, &mut state);
+        assert_eq!((state.offset(), state.selected()), (0, Some(1)));
+    }
+}
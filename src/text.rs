@@ -0,0 +1,143 @@
+//! ANSI-aware text measurement helpers.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A piece of a string: either an escape sequence or a single grapheme
+/// cluster (what the terminal draws as one character).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Segment<'a> {
+    Escape(&'a str),
+    Grapheme(&'a str),
+}
+
+/// Splits `s` into escape sequences (CSI and OSC) and grapheme clusters.
+pub(crate) fn segments(s: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        if rest.starts_with('\x1b') {
+            let len = escape_len(rest.as_bytes());
+            let (escape, tail) = rest.split_at(len);
+            rest = tail;
+            return Some(Segment::Escape(escape));
+        }
+        let grapheme = rest.graphemes(true).next()?;
+        rest = &rest[grapheme.len()..];
+        Some(Segment::Grapheme(grapheme))
+    })
+}
+
+/// Length in bytes of the escape sequence at the start of `bytes`.
+fn escape_len(bytes: &[u8]) -> usize {
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |i| i + 3),
+        Some(b']') => {
+            let mut i = 2;
+            while i < bytes.len() {
+                match bytes[i] {
+                    0x07 => return i + 1,
+                    0x1b if bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+                    _ => i += 1,
+                }
+            }
+            bytes.len()
+        }
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+/// Number of terminal columns `s` occupies when printed.
+///
+/// Counts grapheme clusters rather than chars, so combining marks and emoji
+/// sequences take the width of a single character, and East Asian wide
+/// characters take two columns. ANSI escape sequences and control characters
+/// take none.
+///
+/// ```
+/// use trace_tally::widgets::display_width;
+///
+/// assert_eq!(display_width("build"), 5);
+/// assert_eq!(display_width("\x1b[32m✔\x1b[0m"), 1);
+/// assert_eq!(display_width("日本語"), 6);
+/// assert_eq!(display_width("e\u{301}"), 1);
+/// assert_eq!(display_width("🌑"), 2);
+/// ```
+pub fn display_width(s: &str) -> usize {
+    segments(s)
+        .map(|seg| match seg {
+            Segment::Escape(_) => 0,
+            Segment::Grapheme(g) => grapheme_width(g),
+        })
+        .sum()
+}
+
+/// Columns taken by a single grapheme cluster.
+pub(crate) fn grapheme_width(g: &str) -> usize {
+    if g.starts_with(char::is_control) {
+        return 0;
+    }
+    // Emoji sequences are drawn as one glyph at most two columns wide.
+    g.width().min(2)
+}
+
+/// Number of terminal rows `line` occupies when wrapped at `width` columns.
+pub(crate) fn rows(line: &str, width: usize) -> usize {
+    let width = width.max(1);
+    let mut column = 0;
+    let mut rows = 1;
+    for seg in segments(line) {
+        if let Segment::Grapheme(g) = seg {
+            rows += wrap(&mut column, grapheme_width(g), width);
+        }
+    }
+    rows
+}
+
+/// Advances `column` past a character `w` columns wide, returning 1 if the
+/// terminal wraps it onto a new row first.
+///
+/// A wide character that doesn't fit in the last column wraps as a whole.
+pub(crate) fn wrap(column: &mut usize, w: usize, width: usize) -> usize {
+    let wrapped = w > 0 && *column + w > width;
+    if wrapped {
+        *column = 0;
+    }
+    *column += w;
+    usize::from(wrapped)
+}
+
+/// Cuts `s` to at most `width` columns, ending with `ellipsis` when shortened.
+///
+/// Escape sequences past the cut are kept so styles are still reset.
+pub(crate) fn truncate<'s>(s: &'s str, width: usize, ellipsis: &str) -> std::borrow::Cow<'s, str> {
+    if display_width(s) <= width {
+        return s.into();
+    }
+    let ellipsis = match display_width(ellipsis) {
+        w if w <= width => ellipsis,
+        _ => "",
+    };
+    let budget = width - display_width(ellipsis);
+    let mut out = String::with_capacity(s.len());
+    let mut used = 0;
+    let mut cut = false;
+    for seg in segments(s) {
+        match seg {
+            Segment::Escape(escape) => out.push_str(escape),
+            Segment::Grapheme(_) if cut => {}
+            Segment::Grapheme(g) if used + grapheme_width(g) <= budget => {
+                used += grapheme_width(g);
+                out.push_str(g);
+            }
+            Segment::Grapheme(_) => {
+                cut = true;
+                out.push_str(ellipsis);
+            }
+        }
+    }
+    out.into()
+}
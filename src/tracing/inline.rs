@@ -1,7 +1,7 @@
 use std::sync::Mutex;
 
 use super::{ActionHandler, TaskLayer, TraceMapper};
-use crate::{Action, Renderer, TaskRenderer};
+use crate::{Action, OutputMode, Renderer, TaskRenderer};
 
 /// Renders immediately on every action. No channel, no background thread.
 pub struct InlineHandler<R: Renderer, W: std::io::Write + Send + 'static> {
@@ -23,7 +23,9 @@ impl<R: Renderer + 'static, W: std::io::Write + Send + 'static> ActionHandler<R>
 /// Creates a tracing layer that renders immediately on every action.
 ///
 /// Each span open/close and event triggers a full re-render to `writer`.
-/// No background thread or channel is needed.
+/// No background thread or channel is needed. Like a
+/// [`RenderLoop`](crate::RenderLoop), it prints append-only output when
+/// stderr isn't a terminal (see [`OutputMode::Auto`]).
 ///
 /// ```rust,ignore
 /// let layer = inline_layer::<MyMapper, MyRenderer, _>(
@@ -39,6 +41,6 @@ where
     W: std::io::Write + Send + 'static,
 {
     TaskLayer::new(InlineHandler {
-        inner: Mutex::new((TaskRenderer::new(renderer), writer)),
+        inner: Mutex::new((TaskRenderer::new(renderer).mode(OutputMode::Auto), writer)),
     })
 }
@@ -1,13 +1,15 @@
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use tracing::Subscriber;
 use tracing::span::{Attributes, Id};
 use tracing_subscriber::Layer;
-use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::registry::{ExtensionsMut, LookupSpan, SpanRef};
 
 use super::TraceMapper;
+use super::rate_limit::{RateLimit, RateState, RootRate};
 use crate::{Action, Renderer, TaskId};
 
 /// Action delivery strategy.
@@ -18,7 +20,10 @@ pub trait ActionHandler<R: Renderer>: 'static {
 /// A `tracing` [`tracing_subscriber::Layer`] that captures spans and events as task-tree [`Action`]s.
 pub struct TaskLayer<M, R, H> {
     pub(crate) handler: H,
+    key: LayerKey,
     ids: IdGenerator,
+    rate_limit: Option<RateLimit>,
+    root_rate: RootRate,
     _mapper: PhantomData<M>,
     _renderer: PhantomData<R>,
 }
@@ -32,17 +37,69 @@ where
     pub fn new(handler: H) -> Self {
         Self {
             handler,
+            key: LayerKey::next(),
             ids: IdGenerator::default(),
+            rate_limit: None,
+            root_rate: RootRate::default(),
             _mapper: PhantomData,
             _renderer: PhantomData,
         }
     }
 
-    fn task_id<S>(&self, span: &tracing_subscriber::registry::SpanRef<'_, S>) -> Option<TaskId>
+    /// Admits at most `max_events` events per task in each `per` window.
+    ///
+    /// Excess events are dropped before [`TraceMapper::map_event`] runs, so
+    /// they cost no allocation. The number dropped is reported once per
+    /// window (and when the task closes) as [`Action::EventsSuppressed`], and
+    /// drawn by the presets as a `… suppressed 12 events` marker.
+    ///
+    /// ```rust,ignore
+    /// let layer = MyMapper::channel_layer(tx)
+    ///     .rate_limit(20, Duration::from_secs(1));
+    /// ```
+    pub fn rate_limit(mut self, max_events: usize, per: Duration) -> Self {
+        self.rate_limit = Some(RateLimit::new(max_events, per));
+        self
+    }
+
+    /// Reports events suppressed outside any task once their window is over.
+    ///
+    /// Spans report theirs when they close, but the root never does, so this
+    /// runs on every span and event that doesn't go through the root's budget.
+    /// It only takes the root's lock once a count is due.
+    fn flush_root(&self, now: Instant) {
+        let Some(limit) = &self.rate_limit else {
+            return;
+        };
+        let suppressed = self.root_rate.expire(limit, now);
+        if suppressed > 0 {
+            self.handler.handle(Action::EventsSuppressed {
+                parent: None,
+                count: suppressed,
+            });
+        }
+    }
+
+    fn task_id<S>(&self, span: &SpanRef<'_, S>) -> Option<TaskId>
     where
         S: Subscriber + for<'a> LookupSpan<'a>,
     {
-        span.extensions().get::<TaskIdExt>().map(|ext| ext.0)
+        span.extensions()
+            .get::<Scoped<TaskId>>()
+            .and_then(|ids| ids.get(self.key))
+            .copied()
+    }
+
+    /// Walks up from `span` to the closest span that was mapped to a task.
+    ///
+    /// Spans skipped by [`TraceMapper::map_span`] carry no task id for this
+    /// layer, so their descendants re-parent onto the nearest mapped ancestor.
+    fn nearest_task<'a, S>(&self, span: SpanRef<'a, S>) -> Option<(SpanRef<'a, S>, TaskId)>
+    where
+        S: Subscriber + for<'b> LookupSpan<'b>,
+    {
+        span.scope()
+            .find_map(|span| self.task_id(&span).map(|id| (span, id)))
     }
 }
 
@@ -59,35 +116,87 @@ where
         id: &Id,
         ctx: tracing_subscriber::layer::Context<'_, S>,
     ) {
-        let task_id = self.ids.next();
-        if let Some(span) = ctx.span(id) {
-            span.extensions_mut().insert(TaskIdExt(task_id));
+        let Some(data) = M::map_span(attrs) else {
+            return;
+        };
+
+        if self.rate_limit.is_some() {
+            self.flush_root(Instant::now());
         }
+        let task_id = self.ids.next();
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        scoped_mut(&mut span.extensions_mut(), self.key, || task_id);
 
-        let parent_id = ctx
-            .span(id)
-            .and_then(|s| s.parent())
-            .and_then(|parent| self.task_id(&parent));
+        let parent_id = span
+            .parent()
+            .and_then(|parent| self.nearest_task(parent))
+            .map(|(_, id)| id);
 
         self.handler.handle(Action::TaskStart {
             id: task_id,
             parent: parent_id,
-            data: M::map_span(attrs),
+            data,
         });
     }
 
     fn on_close(&self, id: Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
-        if let Some(task_id) = ctx.span(&id).and_then(|span| self.task_id(&span)) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        if self.rate_limit.is_some() {
+            self.flush_root(Instant::now());
+        }
+        if let Some(task_id) = self.task_id(&span) {
+            let suppressed = span
+                .extensions_mut()
+                .get_mut::<Scoped<RateState>>()
+                .and_then(|states| states.get_mut(self.key))
+                .map_or(0, RateState::take_suppressed);
+            if suppressed > 0 {
+                self.handler.handle(Action::EventsSuppressed {
+                    parent: Some(task_id),
+                    count: suppressed,
+                });
+            }
             self.handler.handle(Action::TaskEnd { id: task_id });
         }
     }
 
     fn on_event(&self, event: &tracing::Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
-        let parent = ctx.lookup_current().and_then(|span| self.task_id(&span));
-        self.handler.handle(Action::Event {
-            parent,
-            data: M::map_event(event),
-        });
+        let owner = ctx
+            .lookup_current()
+            .and_then(|span| self.nearest_task(span));
+        let parent = owner.as_ref().map(|(_, id)| *id);
+
+        if let Some(limit) = &self.rate_limit {
+            let now = Instant::now();
+            let verdict = match &owner {
+                Some((span, _)) => {
+                    self.flush_root(now);
+                    let mut extensions = span.extensions_mut();
+                    limit.admit(
+                        scoped_mut(&mut extensions, self.key, RateState::default),
+                        now,
+                    )
+                }
+                None => self.root_rate.admit(limit, now),
+            };
+            if verdict.suppressed > 0 {
+                self.handler.handle(Action::EventsSuppressed {
+                    parent,
+                    count: verdict.suppressed,
+                });
+            }
+            if !verdict.admitted {
+                return;
+            }
+        }
+
+        if let Some(data) = M::map_event(event) {
+            self.handler.handle(Action::Event { parent, data });
+        }
     }
 }
 
@@ -108,5 +217,51 @@ impl IdGenerator {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct TaskIdExt(TaskId);
+/// Identifies a [`TaskLayer`] instance within a subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LayerKey(usize);
+
+impl LayerKey {
+    fn next() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Span extension data keyed by the owning layer.
+///
+/// Extensions are keyed by type alone, so without this several `TaskLayer`s
+/// in one registry would overwrite each other's task ids and rate state.
+#[derive(Debug)]
+struct Scoped<T>(Vec<(LayerKey, T)>);
+
+impl<T> Scoped<T> {
+    fn get(&self, key: LayerKey) -> Option<&T> {
+        self.0.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    fn get_mut(&mut self, key: LayerKey) -> Option<&mut T> {
+        self.0.iter_mut().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+}
+
+/// Returns this layer's entry in the span's [`Scoped<T>`] extension,
+/// inserting `init()` if it has none yet.
+fn scoped_mut<'a, T: Send + Sync + 'static>(
+    extensions: &'a mut ExtensionsMut<'_>,
+    key: LayerKey,
+    init: impl FnOnce() -> T,
+) -> &'a mut T {
+    if extensions.get_mut::<Scoped<T>>().is_none() {
+        extensions.insert(Scoped::<T>(Vec::new()));
+    }
+    let scoped = extensions.get_mut::<Scoped<T>>().unwrap();
+    let index = match scoped.0.iter().position(|(k, _)| *k == key) {
+        Some(index) => index,
+        None => {
+            scoped.0.push((key, init()));
+            scoped.0.len() - 1
+        }
+    };
+    &mut scoped.0[index].1
+}
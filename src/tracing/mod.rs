@@ -3,6 +3,7 @@ use crate::Renderer;
 mod channel;
 mod inline;
 mod layer;
+mod rate_limit;
 
 pub(crate) use channel::ChannelHandler;
 pub use channel::{ActionTransport, channel_layer};
@@ -22,11 +23,24 @@ pub(crate) use layer::{ActionHandler, TaskLayer};
 ///     type EventData = String;
 ///     type TaskData = String;
 ///
-///     fn map_span(attrs: &tracing::span::Attributes<'_>) -> String {
-///         attrs.metadata().name().to_string()
+///     fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
+///         Some(attrs.metadata().name().to_string())
 ///     }
-///     fn map_event(event: &tracing::Event<'_>) -> String {
-///         format!("{:?}", event)
+///     fn map_event(event: &tracing::Event<'_>) -> Option<String> {
+///         Some(format!("{:?}", event))
+///     }
+/// }
+/// ```
+///
+/// Returning `None` skips the span or event. A skipped span is transparent:
+/// its children and events attach to the nearest mapped ancestor instead.
+///
+/// ```rust,ignore
+/// fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<String> {
+///     // Hide noisy library spans, keep our own.
+///     match attrs.metadata().target().starts_with("my_app") {
+///         true => Some(attrs.metadata().name().to_string()),
+///         false => None,
 ///     }
 /// }
 /// ```
@@ -36,10 +50,10 @@ pub trait TraceMapper: 'static {
     /// Data stored per task (e.g. a task name or metadata).
     type TaskData;
 
-    /// Converts span attributes into task data.
-    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Self::TaskData;
-    /// Converts a tracing event into event data.
-    fn map_event(event: &tracing::Event<'_>) -> Self::EventData;
+    /// Converts span attributes into task data, or `None` to make the span transparent.
+    fn map_span(attrs: &tracing::span::Attributes<'_>) -> Option<Self::TaskData>;
+    /// Converts a tracing event into event data, or `None` to drop the event.
+    fn map_event(event: &tracing::Event<'_>) -> Option<Self::EventData>;
 }
 
 /// Convenience constructors for building tracing layers.
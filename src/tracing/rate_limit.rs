@@ -0,0 +1,128 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Per-task event budget applied by [`super::TaskLayer::rate_limit`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimit {
+    max_events: usize,
+    per: Duration,
+}
+
+/// Fixed window state stored per span (and once for the root).
+#[derive(Debug)]
+pub(crate) struct RateState {
+    window_start: Instant,
+    admitted: usize,
+    suppressed: usize,
+}
+
+impl Default for RateState {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            admitted: 0,
+            suppressed: 0,
+        }
+    }
+}
+
+impl RateState {
+    /// Takes the number of events suppressed since the last flush.
+    pub(crate) fn take_suppressed(&mut self) -> usize {
+        std::mem::take(&mut self.suppressed)
+    }
+}
+
+/// Rate state for events outside any span, shared by every thread.
+#[derive(Debug)]
+pub(crate) struct RootRate {
+    state: Mutex<RateState>,
+    /// When the suppressed count is due, in nanoseconds since `epoch`, or 0
+    /// if nothing is pending. Lets [`expire`](Self::expire) skip the lock.
+    due: AtomicU64,
+    epoch: Instant,
+}
+
+impl Default for RootRate {
+    fn default() -> Self {
+        Self {
+            state: Mutex::default(),
+            due: AtomicU64::new(0),
+            epoch: Instant::now(),
+        }
+    }
+}
+
+impl RootRate {
+    pub(crate) fn admit(&self, limit: &RateLimit, now: Instant) -> Verdict {
+        let mut state = self.state.lock().unwrap();
+        let verdict = limit.admit(&mut state, now);
+        self.set_due(limit, &state);
+        verdict
+    }
+
+    /// Like [`RateLimit::expire`], but only locks once a count is due.
+    pub(crate) fn expire(&self, limit: &RateLimit, now: Instant) -> usize {
+        let due = self.due.load(Ordering::Relaxed);
+        if due == 0 || self.nanos(now) < due {
+            return 0;
+        }
+        let mut state = self.state.lock().unwrap();
+        let suppressed = limit.expire(&mut state, now);
+        self.set_due(limit, &state);
+        suppressed
+    }
+
+    fn set_due(&self, limit: &RateLimit, state: &RateState) {
+        let due = match state.suppressed {
+            0 => 0,
+            _ => self.nanos(state.window_start + limit.per).max(1),
+        };
+        self.due.store(due, Ordering::Relaxed);
+    }
+
+    fn nanos(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.epoch).as_nanos() as u64
+    }
+}
+
+/// Outcome of [`RateLimit::admit`].
+pub(crate) struct Verdict {
+    /// Whether the event fits in the current window.
+    pub(crate) admitted: bool,
+    /// Events suppressed in the previous window, reported once it expires.
+    pub(crate) suppressed: usize,
+}
+
+impl RateLimit {
+    pub(crate) fn new(max_events: usize, per: Duration) -> Self {
+        Self { max_events, per }
+    }
+
+    /// Starts a new window if the current one is over, returning the events
+    /// suppressed in the old one.
+    pub(crate) fn expire(&self, state: &mut RateState, now: Instant) -> usize {
+        if now.duration_since(state.window_start) < self.per {
+            return 0;
+        }
+        state.window_start = now;
+        state.admitted = 0;
+        state.take_suppressed()
+    }
+
+    /// Counts an event against the current window. Windows are fixed: each
+    /// starts with the first event after the previous one ends.
+    pub(crate) fn admit(&self, state: &mut RateState, now: Instant) -> Verdict {
+        let suppressed = self.expire(state, now);
+        let admitted = state.admitted < self.max_events;
+        match admitted {
+            true => state.admitted += 1,
+            false => state.suppressed += 1,
+        }
+        Verdict {
+            admitted,
+            suppressed,
+        }
+    }
+}
@@ -1,60 +1,7 @@
-use std::io::Write;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::task::{EventIndex, Task, TaskStore};
-use crate::{Renderer, TaskId};
-
-/// Write target with ANSI cursor control for frame clearing.
-///
-/// Wraps an [`std::io::Write`] target. Use `write!` / `writeln!` to produce
-/// output within renderer callbacks.
-///
-/// ```rust,ignore
-/// fn render_task_line(
-///     &mut self, f: &mut FrameWriter<'_>, task: &TaskView<'_, Self>,
-/// ) -> std::io::Result<()> {
-///     writeln!(f, "Task: {}", task.data())
-/// }
-/// ```
-pub struct FrameWriter<'a> {
-    target: &'a mut dyn Write,
-    frame_lines: usize,
-}
-
-impl<'a> FrameWriter<'a> {
-    pub(crate) fn new(target: &'a mut dyn Write, frame_lines: usize) -> Self {
-        Self {
-            target,
-            frame_lines,
-        }
-    }
-
-    pub(crate) fn clear_frame(&mut self) -> Result<(), std::io::Error> {
-        let lines_drawn = self.frame_lines;
-        if lines_drawn > 0 {
-            write!(self, "\r\x1b[{}A\x1b[2K\x1b[J", lines_drawn).unwrap();
-            self.target.flush()?;
-        }
-        self.frame_lines = 0;
-        Ok(())
-    }
-
-    pub(crate) fn frame_lines(&self) -> usize {
-        self.frame_lines
-    }
-}
-
-impl<'a> Write for FrameWriter<'a> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let newlines = buf.iter().filter(|&&b| b == b'\n').count();
-        self.frame_lines += newlines;
-        self.target.write(buf)
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.target.flush()
-    }
-}
+use crate::{Renderer, TaskId, TaskSummary};
 
 /// Read-only view of a task, passed to [`Renderer`] callbacks.
 ///
@@ -98,6 +45,46 @@ impl<'a, R: Renderer> TaskView<'a, R> {
         self.tasks.task(&self.id).started_at.elapsed()
     }
 
+    /// How long the task ran: frozen once it finishes, the live
+    /// [`elapsed`](Self::elapsed) time while it is active.
+    pub fn duration(&self) -> Duration {
+        let task = self.tasks.task(&self.id);
+        match task.finished_at {
+            Some(at) => at.duration_since(task.started_at),
+            None => task.started_at.elapsed(),
+        }
+    }
+
+    /// How long since this task finished, or zero while it is active.
+    pub(crate) fn finished_for(&self) -> Duration {
+        self.tasks
+            .task(&self.id)
+            .finished_at
+            .map_or(Duration::ZERO, |at| at.elapsed())
+    }
+
+    /// Counts the task's finished descendants and measures its duration.
+    ///
+    /// See [`Renderer::render_summary_line`].
+    pub fn summary(&self) -> TaskSummary {
+        let task = self.tasks.task(&self.id);
+        let mut summary = TaskSummary {
+            duration: self.duration(),
+            ..TaskSummary::default()
+        };
+        let mut stack = task.subtasks.iter().copied().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            let task = self.tasks.task(&id);
+            if task.completed {
+                summary.completed += 1;
+            } else if task.cancelled {
+                summary.cancelled += 1;
+            }
+            stack.extend(task.subtasks.iter());
+        }
+        summary
+    }
+
     /// Returns `true` if the task is active (not completed or cancelled).
     pub fn active(&self) -> bool {
         !self.completed() && !self.cancelled()
@@ -129,22 +116,48 @@ impl<'a, R: Renderer> TaskView<'a, R> {
             .map(move |id| EventView::new(self.tasks, self.id, id))
     }
 
+    /// Returns how many events on this task were dropped by a rate limit.
+    ///
+    /// See `TaskLayer::rate_limit`.
+    pub fn suppressed_events(&self) -> usize {
+        self.tasks.task(&self.id).suppressed
+    }
+
     /// Returns an iterator over the task's direct children.
+    ///
+    /// Children left out of a frame clipped to the terminal height are skipped.
     pub fn subtasks<'b>(
         &'b self,
     ) -> impl DoubleEndedIterator<Item = TaskView<'b, R>> + ExactSizeIterator {
-        self.tasks
-            .task(&self.id)
-            .subtasks
-            .iter()
-            .map(move |id| TaskView::new(self.tasks, *id))
-    }
-
-    /// Returns the position of this task among its parent's children.
+        let subtasks = &self.tasks.task(&self.id).subtasks;
+        // Only a clipped frame has children to skip.
+        let visible = (!self.tasks.hidden.is_empty()).then(|| {
+            subtasks
+                .iter()
+                .filter(|id| !self.tasks.hidden.contains(id))
+                .copied()
+                .collect::<Vec<_>>()
+        });
+        let len = visible.as_ref().map_or(subtasks.len(), Vec::len);
+        (0..len).map(move |i| {
+            let id = visible.as_ref().map_or(subtasks[i], |ids| ids[i]);
+            TaskView::new(self.tasks, id)
+        })
+    }
+
+    /// Returns the position of this task among its parent's (visible) children.
     pub fn index(&self) -> usize {
         let parent = self.tasks.task(&self.id).parent.unwrap();
         let parent = self.tasks.task(&parent);
-        parent.subtasks.get_index_of(&self.id).unwrap()
+        if self.tasks.hidden.is_empty() {
+            return parent.subtasks.get_index_of(&self.id).unwrap();
+        }
+        parent
+            .subtasks
+            .iter()
+            .filter(|id| !self.tasks.hidden.contains(id))
+            .position(|id| *id == self.id)
+            .unwrap()
     }
 
     /// Create a view of another task in the same tree.
@@ -193,6 +206,11 @@ impl<'a, R: Renderer> EventView<'a, R> {
         self.get_task().depth
     }
 
+    /// When the renderer received this event.
+    pub fn timestamp(&self) -> Instant {
+        self.get_task().event_times[self.id.0]
+    }
+
     /// Returns the TaskView of the owning task.
     pub fn task<'b>(&'b self) -> TaskView<'b, R> {
         TaskView::new(self.tasks, self.task)
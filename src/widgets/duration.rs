@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::{Renderer, TaskView};
+
+/// A duration formatted for people.
+///
+/// ```rust,ignore
+/// let d = Duration::from_millis(83_456);
+/// HumanDuration::new(d);             // => 1m 23s
+/// HumanDuration::new(d).clock();     // => 01:23
+/// HumanDuration::new(d).precise();   // => 1m 23.456s
+/// HumanDuration::new(d).fixed();     // => " 1m 23s", never changes width
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HumanDuration {
+    duration: Duration,
+    format: Format,
+    fixed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Compact,
+    Clock,
+    Precise,
+}
+
+impl HumanDuration {
+    /// Formats `duration` in the [compact](Self::compact) style.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            format: Format::Compact,
+            fixed: false,
+        }
+    }
+
+    /// Two units at most: `4.1s`, `1m 23s`, `2h 5m` (the default).
+    pub fn compact(mut self) -> Self {
+        self.format = Format::Compact;
+        self
+    }
+
+    /// A stopwatch: `01:23`, or `1:02:03` past an hour.
+    pub fn clock(mut self) -> Self {
+        self.format = Format::Clock;
+        self
+    }
+
+    /// Down to the millisecond: `1.234s`, `1m 23.456s`.
+    pub fn precise(mut self) -> Self {
+        self.format = Format::Precise;
+        self
+    }
+
+    /// Pads to a constant width so a column of durations doesn't jitter as
+    /// they tick.
+    ///
+    /// Compact durations take 7 columns up to 100 hours, clock durations
+    /// always show hours (`00:01:23`), and precise durations take 11 columns
+    /// up to an hour.
+    pub fn fixed(mut self) -> Self {
+        self.fixed = true;
+        self
+    }
+}
+
+impl std::fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.duration.as_secs();
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        let millis = self.duration.subsec_millis();
+        let text = match (self.format, self.fixed) {
+            (Format::Compact, false) => match secs {
+                0..60 => format!("{:.1}s", self.duration.as_secs_f64()),
+                60..3600 => format!("{m}m {s}s"),
+                _ => format!("{h}h {m}m"),
+            },
+            (Format::Compact, true) => match secs {
+                0..60 => format!("{:>6.1}s", self.duration.as_secs_f64()),
+                60..3600 => format!("{m:>2}m {s:02}s"),
+                _ => format!("{h:>2}h {m:02}m"),
+            },
+            (Format::Clock, false) if h == 0 => format!("{m:02}:{s:02}"),
+            (Format::Clock, false) => format!("{h}:{m:02}:{s:02}"),
+            (Format::Clock, true) => format!("{h:02}:{m:02}:{s:02}"),
+            (Format::Precise, fixed) => {
+                let text = match secs {
+                    0..60 => format!("{s}.{millis:03}s"),
+                    60..3600 => format!("{m}m {s:02}.{millis:03}s"),
+                    _ => format!("{h}h {m:02}m {s:02}.{millis:03}s"),
+                };
+                match fixed {
+                    true => format!("{text:>11}"),
+                    false => text,
+                }
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+/// How long a task ran, or has been running so far.
+///
+/// Shorthand for a [`HumanDuration`] of [`TaskView::duration`], which stops
+/// counting once the task finishes:
+///
+/// ```rust,ignore
+/// fn render_task_line(&mut self, f: &mut FrameWriter<'_>, task: &TaskView<'_, Self>) -> io::Result<()> {
+///     writeln!(f, "{} {}", task.data(), Elapsed::of(task).clock())
+/// }
+/// // => build 00:04
+/// ```
+pub struct Elapsed;
+
+impl Elapsed {
+    pub fn of<R: Renderer>(task: &TaskView<'_, R>) -> HumanDuration {
+        HumanDuration::new(task.duration())
+    }
+}
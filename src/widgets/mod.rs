@@ -7,11 +7,25 @@
 //! ```rust,ignore
 //! let bar = ProgressBar::new(45, 100).width(30);
 //! writeln!(f, "{} {bar}", task.data())?;
-//! // => my_task [██████████████░░░░░░░░░░░░░░░░]  45%
+//! // => my_task [█████████████▌░░░░░░░░░░░░░░░░]  45%
 //!
 //! // Custom fill characters:
 //! let bar = ProgressBar::new(3, 10).chars('#', '.');
 //! // => [######..............] 30%
+//!
+//! // Done and total after the percentage:
+//! let bar = ProgressBar::new(done, total).width(6).label(Bytes::new);
+//! // => [██▋░░░]  44% 450 MiB / 1.0 GiB
+//!
+//! // Colors, and a fraction instead of the percentage:
+//! let bar = ProgressBar::new(46, 100)
+//!     .filled_style(Style::new().green())
+//!     .suffix(BarSuffix::Fraction);
+//! // => [█████████▏░░░░░░░░░░] 46/100
+//!
+//! // A bouncing block while the total is unknown:
+//! let bar = ProgressBar::new(done, 0).pulse(self.tick);
+//! // => [░░░█████░░░░░░░░░░░░]
 //! ```
 //!
 //! # Spinner
@@ -31,6 +45,66 @@
 //! let s = Spinner::custom(&["🌑", "🌒", "🌓", "🌔", "🌕"]);
 //! ```
 //!
+//! # Durations
+//!
+//! [`HumanDuration`] formats a [`Duration`](std::time::Duration) in a
+//! compact (`1m 23s`), clock (`01:23`) or precise (`1.234s`) style, optionally
+//! padded to a fixed width. [`Elapsed`] does the same for a task, freezing
+//! once the task finishes:
+//!
+//! ```rust,ignore
+//! writeln!(f, "{} {}", task.data(), Elapsed::of(task).fixed())?;
+//! // => build  1m 05s
+//! ```
+//!
+//! # Bytes and counts
+//!
+//! [`Bytes`] formats a size in binary (`12.4 MiB`) or SI (`13.0 MB`) units,
+//! and [`Count`] formats a number with thousands separators (`1,234,567`)
+//! or compactly (`1.2M`):
+//!
+//! ```rust,ignore
+//! writeln!(f, "{} {} files, {}", task.data(), Count::new(files), Bytes::new(size))?;
+//! // => index 12,408 files, 3.2 GiB
+//! ```
+//!
+//! # Throughput and ETA
+//!
+//! [`RateEstimator`] smooths a task's progress counter over time. Keep one
+//! per [`TaskId`](crate::TaskId) in your renderer and format it with
+//! [`Rate`] and [`Eta`], which show `stalled` once progress stops short of
+//! the total and `-:--` while the total is unknown:
+//!
+//! ```rust,ignore
+//! let rate = self.rates.entry(task.id()).or_default();
+//! rate.set_total(total);
+//! rate.update(done);
+//! writeln!(f, "{} {} · ETA {}", task.data(), rate.rate().bytes(), rate.eta(total))?;
+//! // => download 12.4 MiB/s · ETA 0:42
+//! ```
+//!
+//! # Sparkline
+//!
+//! [`Sparkline`] charts recent samples in one line, such as a queue length
+//! pushed on each frame, or how many events a task received per second:
+//!
+//! ```rust,ignore
+//! let activity = Sparkline::event_rate(task, 10, Duration::from_secs(1));
+//! writeln!(f, "{} {activity}", task.data())?;
+//! // => worker-3 ▇▇▆▇▅▃▂▁▁▁
+//! ```
+//!
+//! # Display width
+//!
+//! [`display_width`] measures how many terminal columns a string takes,
+//! counting wide (CJK) characters and emoji as two and skipping escape
+//! sequences, for aligning columns by hand:
+//!
+//! ```rust,ignore
+//! let pad = 12usize.saturating_sub(display_width(&name));
+//! writeln!(f, "{name}{} {elapsed}", " ".repeat(pad))?;
+//! ```
+//!
 //! # Tree indent
 //!
 //! [`TreeIndent`] produces box-drawing prefixes for hierarchical task trees:
@@ -51,11 +125,28 @@
 //! │   └── grandchild b
 //! └── child 2
 //! ```
+//!
+//! # Glyphs
+//!
+//! Widgets draw with the [`Glyphs`](crate::Glyphs) set of the renderer in
+//! use, detected from the locale and `TERM`. On a terminal without Unicode
+//! support the same code renders `|--`, `` `-- ``, `|/-\`, `[###---]` and
+//! `_.-~=+*#`.
 
+mod duration;
 mod progress_bar;
+mod rate;
+mod sparkline;
 mod spinner;
 mod tree_indent;
+mod units;
 
+pub use duration::*;
 pub use progress_bar::*;
+pub use rate::*;
+pub use sparkline::*;
 pub use spinner::*;
 pub use tree_indent::*;
+pub use units::*;
+
+pub use crate::text::display_width;
@@ -1,16 +1,49 @@
+use crate::{ColorSupport, Glyphs, Style};
+
 // A configurable progress bar renderer.
 ///
 /// ```rust,ignore
 /// let bar = ProgressBar::new(45, 100).width(30);
 /// writeln!(f, "{} {bar}", task.data())?;
-/// // => my_task [██████████████░░░░░░░░░░░░░░░░]  45%
+/// // => my_task [█████████████▌░░░░░░░░░░░░░░░░]  45%
+/// ```
+///
+/// With the default characters the last filled cell shows eighths of a cell
+/// (`▏` to `▉`), so slow progress still moves the bar. Styles are resolved
+/// against the [current](ColorSupport::current) color support:
+///
+/// ```rust,ignore
+/// let bar = ProgressBar::new(done, total)
+///     .filled_style(Style::new().green())
+///     .empty_style(Style::new().gray())
+///     .suffix(BarSuffix::Fraction);
+/// // => [█████████▏░░░░░░░░░░] 46/100
 /// ```
 pub struct ProgressBar {
     done: u64,
     total: u64,
     width: usize,
-    filled: char,
-    empty: char,
+    /// `None` follows the current glyph set.
+    chars: Option<(char, char)>,
+    /// Done and total, already formatted.
+    label: Option<(String, String)>,
+    /// Animation step while the total is unknown.
+    pulse: Option<usize>,
+    filled_style: Style,
+    empty_style: Style,
+    suffix: BarSuffix,
+}
+
+/// What a [`ProgressBar`] prints after the bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarSuffix {
+    /// The percentage done: ` 45%` (the default).
+    #[default]
+    Percent,
+    /// Done and total: `45/100`.
+    Fraction,
+    /// Nothing.
+    None,
 }
 
 impl ProgressBar {
@@ -19,19 +52,78 @@ impl ProgressBar {
             done,
             total,
             width: 20,
-            filled: '█',
-            empty: '░',
+            chars: None,
+            label: None,
+            pulse: None,
+            filled_style: Style::new(),
+            empty_style: Style::new(),
+            suffix: BarSuffix::Percent,
         }
     }
 
+    /// Width of the bar in columns, not counting brackets and percentage.
     pub fn width(mut self, w: usize) -> Self {
         self.width = w;
         self
     }
 
+    /// Fill characters. Wide characters count as two columns of the width.
+    ///
+    /// Defaults to the [current](crate::Glyphs::current) glyph set's
+    /// `█` and `░`, or `#` and `-` without Unicode support. Custom characters
+    /// fill whole cells only.
     pub fn chars(mut self, filled: char, empty: char) -> Self {
-        self.filled = filled;
-        self.empty = empty;
+        self.chars = Some((filled, empty));
+        self
+    }
+
+    /// Style of the filled part of the bar.
+    pub fn filled_style(mut self, style: Style) -> Self {
+        self.filled_style = style;
+        self
+    }
+
+    /// Style of the empty part of the bar.
+    pub fn empty_style(mut self, style: Style) -> Self {
+        self.empty_style = style;
+        self
+    }
+
+    /// What follows the bar (default [`BarSuffix::Percent`]).
+    pub fn suffix(mut self, suffix: BarSuffix) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Bounces a block back and forth while the total is unknown (zero),
+    /// one column per `tick`, instead of showing an empty bar at 0%.
+    ///
+    /// ```rust,ignore
+    /// self.tick += 1;
+    /// ProgressBar::new(done, total).pulse(self.tick);
+    /// // => [░░░█████░░░░░░░░░░░░]
+    /// ```
+    ///
+    /// The percentage and fraction are left out, and a [label](Self::label)
+    /// shows only the amount done.
+    pub fn pulse(mut self, tick: usize) -> Self {
+        self.pulse = Some(tick);
+        self
+    }
+
+    /// Shows done and total after the percentage, formatted with `format`:
+    ///
+    /// ```rust,ignore
+    /// ProgressBar::new(done, total).width(6).label(Bytes::new);
+    /// // => [██▋░░░]  44% 450 MiB / 1.0 GiB
+    /// ProgressBar::new(done, total).label(|n| Count::new(n).compact());
+    /// // => [█████░░░░░░░░░░░░░░░]  25% 3.1k / 12.4k
+    /// ```
+    pub fn label<D: std::fmt::Display>(mut self, format: impl Fn(u64) -> D) -> Self {
+        self.label = Some((
+            format(self.done).to_string(),
+            format(self.total).to_string(),
+        ));
         self
     }
 
@@ -41,19 +133,90 @@ impl ProgressBar {
         }
         (self.done as f64 / self.total as f64).clamp(0.0, 1.0)
     }
+
+    /// Whether the bar is pulsing rather than showing a ratio.
+    fn indeterminate(&self) -> bool {
+        self.pulse.is_some() && self.total == 0
+    }
+
+    /// The bar's cells as (empty, filled, empty) runs, filling `width`
+    /// columns.
+    fn cells(&self, glyphs: &Glyphs) -> (String, String, String) {
+        let (filled_char, empty_char) = self.chars.unwrap_or((glyphs.bar_filled, glyphs.bar_empty));
+        let cell = |c: char| super::display_width(c.encode_utf8(&mut [0; 4])).max(1);
+        let filled_width = cell(filled_char);
+        // Repeats `c` over `columns`, padding with spaces where a wide
+        // character doesn't fit.
+        let run = |c: char, columns: usize| {
+            let width = cell(c);
+            let mut out = std::iter::repeat_n(c, columns / width).collect::<String>();
+            out.extend(std::iter::repeat_n(' ', columns % width));
+            out
+        };
+
+        if let Some(tick) = self.pulse.filter(|_| self.total == 0) {
+            let block = (self.width / 4).max(filled_width).min(self.width);
+            let block = block - block % filled_width;
+            let travel = self.width - block;
+            let step = tick % (2 * travel).max(1);
+            let start = if step > travel {
+                2 * travel - step
+            } else {
+                step
+            };
+            let end = self.width - start - block;
+            return (
+                run(empty_char, start),
+                run(filled_char, block),
+                run(empty_char, end),
+            );
+        }
+
+        let exact = self.ratio() * self.width as f64;
+        let mut columns = exact as usize / filled_width * filled_width;
+        let mut filled = run(filled_char, columns);
+        // Partial cells are one column wide, so only the default fill uses them.
+        let partial = match self.chars {
+            None if filled_width == 1 => glyphs.bar_partial,
+            _ => &[],
+        };
+        if !partial.is_empty() && columns < self.width {
+            let steps = ((exact - columns as f64) * (partial.len() + 1) as f64) as usize;
+            if let Some(&c) = steps.checked_sub(1).and_then(|i| partial.get(i)) {
+                filled.push(c);
+                columns += 1;
+            }
+        }
+        let empty = run(empty_char, self.width - columns);
+        (String::new(), filled, empty)
+    }
 }
 
 impl std::fmt::Display for ProgressBar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ratio = self.ratio();
-        let filled = (ratio * self.width as f64) as usize;
-        let empty = self.width - filled;
-        write!(
-            f,
-            "[{}{}] {:3.0}%",
-            std::iter::repeat_n(self.filled, filled).collect::<String>(),
-            std::iter::repeat_n(self.empty, empty).collect::<String>(),
-            ratio * 100.0,
-        )
+        let support = ColorSupport::current();
+        let (before, filled, after) = self.cells(&Glyphs::current());
+        f.write_str("[")?;
+        for (style, text) in [
+            (self.empty_style, before),
+            (self.filled_style, filled),
+            (self.empty_style, after),
+        ] {
+            if !text.is_empty() {
+                write!(f, "{}", style.paint(text, support))?;
+            }
+        }
+        f.write_str("]")?;
+        let indeterminate = self.indeterminate();
+        match (self.suffix, indeterminate) {
+            (_, true) | (BarSuffix::None, _) => {}
+            (BarSuffix::Percent, false) => write!(f, " {:3.0}%", self.ratio() * 100.0)?,
+            (BarSuffix::Fraction, false) => write!(f, " {}/{}", self.done, self.total)?,
+        }
+        match &self.label {
+            Some((done, _)) if indeterminate => write!(f, " {done}"),
+            Some((done, total)) => write!(f, " {done} / {total}"),
+            None => Ok(()),
+        }
     }
 }
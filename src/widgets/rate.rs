@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+use super::Bytes;
+
+/// Samples closer together than this are merged, so several updates in one
+/// frame don't produce a noisy rate.
+const MIN_SAMPLE: Duration = Duration::from_millis(100);
+
+/// Smoothed throughput of a task, for [`Rate`] and [`Eta`].
+///
+/// Feed it the task's progress counter on each frame. The rate is an
+/// exponentially weighted moving average, so a burst or a pause moves it
+/// gradually rather than all at once. Keep one per task in your renderer:
+///
+/// ```rust,ignore
+/// struct MyRenderer {
+///     rates: HashMap<TaskId, RateEstimator>,
+/// }
+///
+/// fn render_task_line(&mut self, f: &mut FrameWriter<'_>, task: &TaskView<'_, Self>) -> io::Result<()> {
+///     let progress = task.data().progress;
+///     let rate = self.rates.entry(task.id()).or_default();
+///     rate.set_total(progress.total);
+///     rate.update(progress.done);
+///     writeln!(f, "{} {} · ETA {}", task.data().name, rate.rate().bytes(), rate.eta(progress.total))
+///     // => download 12.4 MiB/s · ETA 0:42
+/// }
+///
+/// fn on_render_end(&mut self) {
+///     // Forget tasks that are gone, e.g. by tracking the ids seen this frame.
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RateEstimator {
+    half_life: Duration,
+    stall_after: Duration,
+    /// Latest sample the rate was measured from.
+    sample: Option<(Instant, u64)>,
+    /// Smoothed units per second.
+    per_sec: Option<f64>,
+    /// Latest progress value and when it was reported.
+    done: u64,
+    now: Option<Instant>,
+    /// When the progress value last increased.
+    progressed_at: Option<Instant>,
+    /// Expected final progress value, if known.
+    total: Option<u64>,
+}
+
+impl Default for RateEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateEstimator {
+    /// An estimator with a 2 second half-life that reports a stall after
+    /// 5 seconds without progress.
+    pub fn new() -> Self {
+        Self {
+            half_life: Duration::from_secs(2),
+            stall_after: Duration::from_secs(5),
+            sample: None,
+            per_sec: None,
+            done: 0,
+            now: None,
+            progressed_at: None,
+            total: None,
+        }
+    }
+
+    /// How long until an old sample counts for half as much. Shorter reacts
+    /// faster; longer gives a steadier number.
+    pub fn half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// How long without progress before the task counts as stalled.
+    pub fn stall_after(mut self, duration: Duration) -> Self {
+        self.stall_after = duration;
+        self
+    }
+
+    /// The value the counter ends at, if known. A counter that has reached
+    /// it is finished rather than stalled.
+    pub fn set_total(&mut self, total: Option<u64>) {
+        self.total = total;
+    }
+
+    /// Records the task's progress counter, now.
+    pub fn update(&mut self, done: u64) {
+        self.update_at(done, Instant::now());
+    }
+
+    /// Records the task's progress counter as of `now`.
+    ///
+    /// A counter that goes backwards starts the estimate over.
+    pub fn update_at(&mut self, done: u64, now: Instant) {
+        if done < self.done {
+            *self = Self {
+                half_life: self.half_life,
+                stall_after: self.stall_after,
+                total: self.total,
+                ..Self::new()
+            };
+        }
+        if done > self.done || self.progressed_at.is_none() {
+            self.progressed_at = Some(now);
+        }
+        self.done = done;
+        self.now = Some(now);
+
+        let Some((at, then)) = self.sample else {
+            self.sample = Some((now, done));
+            return;
+        };
+        let dt = now.saturating_duration_since(at);
+        if dt < MIN_SAMPLE {
+            return;
+        }
+        let instant = (done - then) as f64 / dt.as_secs_f64();
+        let weight = 1.0 - 0.5f64.powf(dt.as_secs_f64() / self.half_life.as_secs_f64());
+        self.per_sec = Some(match self.per_sec {
+            Some(rate) => rate + weight * (instant - rate),
+            None => instant,
+        });
+        self.sample = Some((now, done));
+    }
+
+    /// Smoothed units per second, once two samples are far enough apart.
+    pub fn per_sec(&self) -> Option<f64> {
+        self.per_sec
+    }
+
+    /// Whether the counter hasn't moved for [`stall_after`](Self::stall_after)
+    /// short of its [total](Self::set_total).
+    pub fn is_stalled(&self) -> bool {
+        if self.reached(self.total) {
+            return false;
+        }
+        match (self.now, self.progressed_at) {
+            (Some(now), Some(at)) => now.saturating_duration_since(at) >= self.stall_after,
+            _ => false,
+        }
+    }
+
+    fn reached(&self, total: Option<u64>) -> bool {
+        total.is_some_and(|total| self.done >= total)
+    }
+
+    /// The current throughput, for display.
+    pub fn rate(&self) -> Rate {
+        Rate {
+            per_sec: self.per_sec,
+            stalled: self.is_stalled(),
+            bytes: false,
+            unit: "",
+        }
+    }
+
+    /// Time left until the counter reaches `total`, for display.
+    ///
+    /// Unknown while the total or the rate is unknown.
+    pub fn eta(&self, total: Option<u64>) -> Eta {
+        let remaining = match (total, self.per_sec) {
+            (Some(total), _) if self.done >= total => Some(Duration::ZERO),
+            (Some(total), Some(rate)) if rate > 0.0 => {
+                let secs = (total - self.done) as f64 / rate;
+                Duration::try_from_secs_f64(secs).ok()
+            }
+            _ => None,
+        };
+        Eta {
+            remaining,
+            stalled: self.is_stalled() && !self.reached(total),
+        }
+    }
+}
+
+/// Throughput from a [`RateEstimator`]: `12.4/s`, `12.4 MiB/s`, or
+/// `stalled`.
+#[derive(Debug, Clone, Copy)]
+pub struct Rate {
+    per_sec: Option<f64>,
+    stalled: bool,
+    bytes: bool,
+    unit: &'static str,
+}
+
+impl Rate {
+    /// Formats the rate as [`Bytes`]: `12.4 MiB/s`.
+    pub fn bytes(mut self) -> Self {
+        self.bytes = true;
+        self
+    }
+
+    /// Names what is counted: `3.5 files/s`.
+    pub fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+}
+
+impl std::fmt::Display for Rate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.stalled {
+            return f.write_str("stalled");
+        }
+        let Some(rate) = self.per_sec else {
+            return f.write_str("-/s");
+        };
+        if self.bytes {
+            return write!(f, "{}/s", Bytes::new(rate.round() as u64));
+        }
+        let sep = if self.unit.is_empty() { "" } else { " " };
+        match rate {
+            r if r < 100.0 => write!(f, "{r:.1}{sep}{}/s", self.unit),
+            r => write!(f, "{r:.0}{sep}{}/s", self.unit),
+        }
+    }
+}
+
+/// Time left from a [`RateEstimator`]: `0:42`, `1:02:03`, `-:--` when
+/// unknown, or `stalled`.
+#[derive(Debug, Clone, Copy)]
+pub struct Eta {
+    remaining: Option<Duration>,
+    stalled: bool,
+}
+
+impl Eta {
+    /// The estimated time left, if known.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining
+    }
+}
+
+impl std::fmt::Display for Eta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.stalled {
+            return f.write_str("stalled");
+        }
+        let Some(remaining) = self.remaining else {
+            return f.write_str("-:--");
+        };
+        // Round up so the last second shows as 0:01 rather than 0:00.
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        match h {
+            0 => write!(f, "{m}:{s:02}"),
+            _ => write!(f, "{h}:{m:02}:{s:02}"),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::{Renderer, TaskView};
+
+/// A one-line chart of recent samples: `▁▂▅▇▆▃`.
+///
+/// Keeps the last `capacity` samples in a ring buffer and draws one column
+/// per sample, oldest on the left, scaled from zero. Feed it a value on each
+/// frame, keeping one per task like a [`RateEstimator`](crate::RateEstimator):
+///
+/// ```rust,ignore
+/// let history = self.queue.entry(task.id()).or_insert_with(|| Sparkline::new(12));
+/// history.push(task.data().queued as f64);
+/// writeln!(f, "{} {history}", task.data())?;
+/// // => worker-3 ▁▂▅▇▆▃
+/// ```
+///
+/// Or build one from the times of a task's events with
+/// [`event_rate`](Self::event_rate).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    samples: VecDeque<f64>,
+    capacity: usize,
+    max: Option<f64>,
+}
+
+impl Sparkline {
+    /// An empty sparkline, `capacity` columns wide.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            max: None,
+        }
+    }
+
+    /// Charts how many of the task's buffered events arrived in each of the
+    /// last `width` periods of `bucket`, ending now.
+    ///
+    /// Only the events the task still holds are counted, so a chart spanning
+    /// longer than it takes a busy task to fill its event buffer undercounts
+    /// the oldest periods.
+    ///
+    /// ```rust,ignore
+    /// writeln!(f, "{} {}", task.data(), Sparkline::event_rate(task, 10, Duration::from_secs(1)))?;
+    /// // => worker-3 ▇▇▆▇▅▃▂▁▁▁
+    /// ```
+    pub fn event_rate<R: Renderer>(task: &TaskView<'_, R>, width: usize, bucket: Duration) -> Self {
+        let now = Instant::now();
+        let mut counts = vec![0u32; width];
+        for event in task.events() {
+            let age = now.saturating_duration_since(event.timestamp());
+            let ago = (age.as_nanos() / bucket.as_nanos().max(1)) as usize;
+            if ago < width {
+                counts[width - 1 - ago] += 1;
+            }
+        }
+        let mut sparkline = Self::new(width);
+        for count in counts {
+            sparkline.push(count as f64);
+        }
+        sparkline
+    }
+
+    /// Value drawn as a full column. Defaults to the largest sample, so the
+    /// chart shows shape rather than scale.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Appends a sample, dropping the oldest once the sparkline is full.
+    pub fn push(&mut self, value: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        if self.capacity > 0 {
+            self.samples.push_back(value);
+        }
+    }
+
+    /// The samples held, oldest first.
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = f64> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Removes every sample.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+impl std::fmt::Display for Sparkline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let levels = crate::Glyphs::current().sparkline;
+        let max = self
+            .max
+            .unwrap_or_else(|| self.samples().fold(0.0, f64::max));
+        // Blank on the left until enough samples arrive, so the width holds.
+        write!(f, "{:1$}", "", self.capacity - self.samples.len())?;
+        for value in self.samples() {
+            let ratio = match max > 0.0 {
+                true => (value / max).clamp(0.0, 1.0),
+                false => 0.0,
+            };
+            let level = (ratio * (levels.len() - 1) as f64).round() as usize;
+            write!(f, "{}", levels[level])?;
+        }
+        Ok(())
+    }
+}
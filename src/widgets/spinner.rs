@@ -1,5 +1,10 @@
+use crate::Glyphs;
+
 /// A frame-based spinner animation.
 ///
+/// Frames are padded to the widest one, so text after the spinner doesn't
+/// shift when frames differ in [display width](super::display_width).
+///
 /// Call [`Spinner::tick`] once per render frame (typically in [`crate::Renderer::on_render_start`])
 /// and [`Spinner::frame`] to get the current character.
 ///
@@ -21,48 +26,60 @@
 /// }
 /// ```
 pub struct Spinner {
-    frames: &'static [&'static str],
+    /// `None` follows the current glyph set's spinner.
+    frames: Option<&'static [&'static str]>,
     index: usize,
 }
 
 impl Spinner {
     /// Braille dot spinner (the most common choice).
+    ///
+    /// Draws the [current](crate::Glyphs::current) glyph set's spinner, so
+    /// it becomes `| / - \` on terminals without Unicode support.
     pub fn dots() -> Self {
         Self {
-            frames: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            frames: None,
             index: 0,
         }
     }
 
     /// Classic line spinner.
     pub fn line() -> Self {
-        Self {
-            frames: &["|", "/", "-", "\\"],
-            index: 0,
-        }
+        Self::custom(&["|", "/", "-", "\\"])
     }
 
     /// Arrow spinner.
     pub fn arrow() -> Self {
+        Self::custom(&["←", "↖", "↑", "↗", "→", "↘", "↓", "↙"])
+    }
+
+    /// Custom frames.
+    pub fn custom(frames: &'static [&'static str]) -> Self {
         Self {
-            frames: &["←", "↖", "↑", "↗", "→", "↘", "↓", "↙"],
+            frames: Some(frames),
             index: 0,
         }
     }
 
-    /// Custom frames.
-    pub fn custom(frames: &'static [&'static str]) -> Self {
-        Self { frames, index: 0 }
+    fn frames(&self) -> &'static [&'static str] {
+        self.frames.unwrap_or(Glyphs::current().spinner)
     }
 
     /// Advance to the next frame.
     pub fn tick(&mut self) {
-        self.index = (self.index + 1) % self.frames.len();
+        self.index = self.index.wrapping_add(1);
     }
 
     /// Current frame string.
     pub fn frame(&self) -> &'static str {
-        self.frames[self.index]
+        let frames = self.frames();
+        frames.get(self.index % frames.len().max(1)).unwrap_or(&"")
+    }
+
+    /// Columns taken by the widest frame.
+    pub fn width(&self) -> usize {
+        let frames = self.frames().iter();
+        frames.map(|f| super::display_width(f)).max().unwrap_or(0)
     }
 }
 
@@ -74,6 +91,8 @@ impl Default for Spinner {
 
 impl std::fmt::Display for Spinner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.frame())
+        let frame = self.frame();
+        let pad = self.width() - super::display_width(frame);
+        write!(f, "{frame}{:pad$}", "")
     }
 }
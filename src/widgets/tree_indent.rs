@@ -1,7 +1,10 @@
-use crate::{Renderer, TaskView};
+use crate::{Glyphs, Renderer, TaskView};
 
 /// Renders tree-drawing characters for a task's position in the hierarchy.
 ///
+/// Draws with the [current](Glyphs::current) glyph set, so it falls back to
+/// `|--` and `` `-- `` on terminals without box-drawing characters.
+///
 /// ```text
 /// root task
 /// ├── child 1
@@ -16,12 +19,49 @@ use crate::{Renderer, TaskView};
 ///     writeln!(f, "{}", task.data())
 /// }
 /// ```
-pub struct TreeIndent(String);
+pub struct TreeIndent {
+    /// Whether each ancestor level (outermost first) is the last sibling.
+    segments: Vec<bool>,
+    /// Draw the last level as a continuation (`│   `) rather than a branch.
+    below: bool,
+}
 
 impl TreeIndent {
+    /// Prefix for the task's own line.
     pub fn of<R: Renderer>(task: &TaskView<'_, R>) -> Self {
+        Self {
+            segments: Self::segments(task),
+            below: false,
+        }
+    }
+
+    /// Prefix for lines drawn under the task, such as its events.
+    ///
+    /// Continues the vertical lines of every level instead of branching:
+    ///
+    /// ```text
+    /// ├── build
+    /// │   compiling 12 crates
+    /// └── test
+    /// ```
+    pub fn below<R: Renderer>(task: &TaskView<'_, R>) -> Self {
+        Self {
+            segments: Self::segments(task),
+            below: true,
+        }
+    }
+
+    /// Drops the outermost level, so root tasks get no connector.
+    pub fn skip_root(mut self) -> Self {
+        if !self.segments.is_empty() {
+            self.segments.remove(0);
+        }
+        self
+    }
+
+    fn segments<R: Renderer>(task: &TaskView<'_, R>) -> Vec<bool> {
         if task.depth() == 0 {
-            return Self(String::new());
+            return Vec::new();
         }
 
         // Walk up the tree by TaskId to avoid borrow conflicts.
@@ -45,24 +85,22 @@ impl TreeIndent {
         }
 
         segments.reverse();
-
-        let mut out = String::new();
-        for (i, &is_last) in segments.iter().enumerate() {
-            let is_self = i == segments.len() - 1;
-            match (is_self, is_last) {
-                (true, true) => out.push_str("└── "),
-                (true, false) => out.push_str("├── "),
-                (false, true) => out.push_str("    "),
-                (false, false) => out.push_str("│   "),
-            }
-        }
-
-        Self(out)
+        segments
     }
 }
 
 impl std::fmt::Display for TreeIndent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        let glyphs = Glyphs::current();
+        for (i, &is_last) in self.segments.iter().enumerate() {
+            let is_self = !self.below && i == self.segments.len() - 1;
+            f.write_str(match (is_self, is_last) {
+                (true, true) => glyphs.last_branch,
+                (true, false) => glyphs.branch,
+                (false, true) => glyphs.space,
+                (false, false) => glyphs.pipe,
+            })?;
+        }
+        Ok(())
     }
 }
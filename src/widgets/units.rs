@@ -0,0 +1,140 @@
+/// A byte count with binary or SI units.
+///
+/// ```rust,ignore
+/// Bytes::new(512);                     // => 512 B
+/// Bytes::new(12_998_144);              // => 12.4 MiB
+/// Bytes::new(471_859_200);             // => 450 MiB
+/// Bytes::new(12_998_144).si();         // => 13.0 MB
+/// Bytes::new(12_998_144).precision(2); // => 12.40 MiB
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes {
+    bytes: u64,
+    si: bool,
+    precision: Option<usize>,
+}
+
+impl Bytes {
+    /// Formats `bytes` in binary units (KiB, MiB, ...).
+    pub fn new(bytes: u64) -> Self {
+        Self {
+            bytes,
+            si: false,
+            precision: None,
+        }
+    }
+
+    /// Powers of 1024: KiB, MiB, GiB (the default).
+    pub fn binary(mut self) -> Self {
+        self.si = false;
+        self
+    }
+
+    /// Powers of 1000: kB, MB, GB.
+    pub fn si(mut self) -> Self {
+        self.si = true;
+        self
+    }
+
+    /// Always shows `digits` decimals past the first unit, instead of one
+    /// below 100 and none above.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+}
+
+impl std::fmt::Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (base, units) = match self.si {
+            true => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+            false => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        };
+        scaled(f, self.bytes as f64, base, &units, " ", self.precision)
+    }
+}
+
+/// A count of things, with thousands separators or compact suffixes.
+///
+/// ```rust,ignore
+/// Count::new(1_234_567);              // => 1,234,567
+/// Count::new(1_234_567).compact();    // => 1.2M
+/// Count::new(3_400).compact();        // => 3.4k
+/// Count::new(999).compact();          // => 999
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Count {
+    count: u64,
+    compact: bool,
+    precision: Option<usize>,
+}
+
+impl Count {
+    /// Formats `count` with thousands separators.
+    pub fn new(count: u64) -> Self {
+        Self {
+            count,
+            compact: false,
+            precision: None,
+        }
+    }
+
+    /// Abbreviates thousands, millions, billions and trillions as `k`, `M`,
+    /// `B` and `T`.
+    pub fn compact(mut self) -> Self {
+        self.compact = true;
+        self
+    }
+
+    /// Always shows `digits` decimals on compact counts, instead of one
+    /// below 100 and none above.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+}
+
+impl std::fmt::Display for Count {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.compact {
+            let units = ["", "k", "M", "B", "T"];
+            return scaled(f, self.count as f64, 1000.0, &units, "", self.precision);
+        }
+        let digits = self.count.to_string();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                f.write_str(",")?;
+            }
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `value` in the largest of `units` (successive powers of `base`)
+/// that keeps it at least 1.
+///
+/// Values in the first unit are whole; the others get `precision` decimals,
+/// or one below 100 and none above so they stay three digits wide.
+pub(super) fn scaled(
+    f: &mut std::fmt::Formatter<'_>,
+    mut value: f64,
+    base: f64,
+    units: &[&str],
+    sep: &str,
+    precision: Option<usize>,
+) -> std::fmt::Result {
+    let mut unit = 0;
+    // Step up early when rounding would print `1024 KiB` or `1000k`.
+    while unit + 1 < units.len() && value >= base - 0.5 {
+        value /= base;
+        unit += 1;
+    }
+    let digits = match (unit, precision) {
+        (0, _) => 0,
+        (_, Some(digits)) => digits,
+        (_, None) if value < 99.95 => 1,
+        (_, None) => 0,
+    };
+    write!(f, "{value:.digits$}{sep}{}", units[unit])
+}
@@ -1,8 +1,93 @@
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Write;
+use std::sync::Arc;
 
+use crate::frame::{FrameOptions, redraw};
+use crate::glyphs::GlyphScope;
+use crate::offscreen::{self, Line};
+use crate::style::ColorScope;
+use crate::summary::RunStats;
 use crate::task::TaskStore;
-use crate::{Action, EventView, FrameWriter, Renderer, TaskId, TaskView};
+use crate::{
+    Action, ColorSupport, EventView, FrameWriter, Glyphs, Renderer, RunSummary, TaskId, TaskView,
+};
+use crate::{term, text};
+
+/// How a [`TaskRenderer`] draws to its target.
+///
+/// ```rust,ignore
+/// // Force append-only output, e.g. when writing to a log file:
+/// let renderer = TaskRenderer::new(MyRenderer::default()).mode(OutputMode::Append);
+///
+/// // Or detect it from the stream you're about to write to:
+/// let renderer = TaskRenderer::new(MyRenderer::default())
+///     .mode(OutputMode::detect(&std::io::stdout()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// [`Interactive`](Self::Interactive) when stderr is a terminal,
+    /// [`Append`](Self::Append) otherwise.
+    #[default]
+    Auto,
+    /// Redraw the active frame in place using ANSI cursor movement.
+    Interactive,
+    /// Print each task start, task finish and event once, in the order they
+    /// happened, without cursor movement. Suited to CI logs and redirected
+    /// output.
+    ///
+    /// [`Renderer::render_task_line`] is called when a task starts and again
+    /// when it finishes (check [`TaskView::active`] to tell them apart), and
+    /// [`Renderer::render_event_line`] once per event.
+    Append,
+}
+
+impl OutputMode {
+    /// Picks [`Interactive`](Self::Interactive) if `stream` is a terminal,
+    /// [`Append`](Self::Append) otherwise.
+    pub fn detect(stream: &impl std::io::IsTerminal) -> Self {
+        match stream.is_terminal() {
+            true => Self::Interactive,
+            false => Self::Append,
+        }
+    }
+
+    fn resolve(self) -> Self {
+        match self {
+            Self::Auto => Self::detect(&std::io::stderr()),
+            mode => mode,
+        }
+    }
+}
+
+/// Which finished tasks leave the live tree for the scrollback.
+enum Commit<R: Renderer> {
+    /// Finished tasks at this depth or shallower.
+    Depth(usize),
+    /// Finished root tasks, plus nested ones matching the predicate.
+    When(Arc<CommitFn<R>>),
+}
+
+type CommitFn<R> = dyn Fn(&TaskView<'_, R>) -> bool + Send + Sync;
+
+impl<R: Renderer> Commit<R> {
+    /// Returns `true` if the finished `task` should be committed.
+    fn applies(&self, task: &TaskView<'_, R>) -> bool {
+        match self {
+            Commit::Depth(depth) => task.depth() <= (*depth).max(1),
+            Commit::When(f) => task.depth() <= 1 || f(task),
+        }
+    }
+}
+
+impl<R: Renderer> Clone for Commit<R> {
+    fn clone(&self) -> Self {
+        match self {
+            Commit::Depth(depth) => Commit::Depth(*depth),
+            Commit::When(f) => Commit::When(f.clone()),
+        }
+    }
+}
 
 /// Receives [`Action`]s, manages the task tree, and drives rendering.
 ///
@@ -19,6 +104,23 @@ use crate::{Action, EventView, FrameWriter, Renderer, TaskId, TaskView};
 pub struct TaskRenderer<R: Renderer> {
     tasks: TaskStore<R>,
     frame_lines: usize,
+    frame: Vec<String>,
+    frame_width: usize,
+    /// Size of the last frame, to notice when the terminal is resized.
+    size: Option<(usize, Option<usize>)>,
+    options: FrameOptions,
+    mode: OutputMode,
+    synchronized: bool,
+    /// Lines rendered as actions arrive in [`OutputMode::Append`], so each
+    /// shows the state it was recorded in; printed on the next render.
+    journal: Vec<u8>,
+    /// First error a renderer hook returned while journaling.
+    journal_error: Option<std::io::ErrorKind>,
+    /// Options the journal is rendered with, resolved once per render
+    /// rather than once per action.
+    journal_options: Option<FrameOptions>,
+    stats: RunStats,
+    commit: Commit<R>,
     r: R,
 }
 
@@ -55,6 +157,17 @@ where
         Self {
             tasks: self.tasks.clone(),
             frame_lines: self.frame_lines,
+            frame: self.frame.clone(),
+            frame_width: self.frame_width,
+            size: self.size,
+            options: self.options,
+            mode: self.mode,
+            synchronized: self.synchronized,
+            journal: self.journal.clone(),
+            journal_error: self.journal_error,
+            journal_options: self.journal_options,
+            stats: self.stats.clone(),
+            commit: self.commit.clone(),
             r: self.r.clone(),
         }
     }
@@ -66,6 +179,21 @@ impl<R: Renderer> TaskRenderer<R> {
         Self {
             tasks: TaskStore::new(),
             frame_lines: 0,
+            frame: Vec::new(),
+            frame_width: 0,
+            size: None,
+            options: FrameOptions {
+                color: Some(ColorSupport::detect()),
+                glyphs: Some(Glyphs::detect()),
+                ..FrameOptions::default()
+            },
+            mode: OutputMode::Interactive,
+            synchronized: true,
+            journal: Vec::new(),
+            journal_error: None,
+            journal_options: None,
+            stats: RunStats::default(),
+            commit: Commit::Depth(1),
             r: renderer,
         }
     }
@@ -77,9 +205,248 @@ impl<R: Renderer> TaskRenderer<R> {
         self
     }
 
+    /// Fix the terminal width instead of querying the terminal each frame.
+    ///
+    /// Useful for tests and for targets that aren't the controlling terminal.
+    pub fn width(mut self, columns: usize) -> Self {
+        self.options.width = Some(columns);
+        self
+    }
+
+    /// Choose between redrawing in place and append-only output.
+    ///
+    /// Defaults to [`OutputMode::Interactive`], since the renderer doesn't
+    /// know which stream [`render`](Self::render) will write to.
+    /// [`OutputMode::Auto`] checks whether stderr is a terminal; use
+    /// [`OutputMode::detect`] for other streams.
+    pub fn mode(mut self, mode: OutputMode) -> Self {
+        self.set_mode(mode);
+        self
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: OutputMode) {
+        self.mode = mode.resolve();
+    }
+
+    /// Override the detected color support used to resolve [`crate::Style`]s.
+    ///
+    /// By default this is detected once from the environment (`NO_COLOR`,
+    /// `CLICOLOR_FORCE`, `TERM`, `COLORTERM`) and whether stderr is a
+    /// terminal. See [`ColorSupport::detect`].
+    pub fn color(mut self, support: ColorSupport) -> Self {
+        self.options.color = Some(support);
+        self
+    }
+
+    /// Override the detected [`Glyphs`] that widgets and presets draw with.
+    ///
+    /// By default this is detected once from the locale and `TERM`. See
+    /// [`Glyphs::detect`].
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.options.glyphs = Some(glyphs);
+        self
+    }
+
+    /// Makes the glyphs and color support of `options` [`Glyphs::current`]
+    /// and [`ColorSupport::current`] while hooks run.
+    fn widget_scope(options: &FrameOptions) -> (GlyphScope, ColorScope) {
+        (
+            GlyphScope::enter(options.glyphs.unwrap_or(Glyphs::UNICODE)),
+            ColorScope::enter(options.color.unwrap_or_default()),
+        )
+    }
+
+    /// Wrap each frame in synchronized-update sequences (DEC mode 2026) so
+    /// supporting terminals paint it atomically, without tearing. Terminals
+    /// without support ignore them. Enabled by default.
+    pub fn synchronized_output(mut self, yes: bool) -> Self {
+        self.synchronized = yes;
+        self
+    }
+
+    /// Cap the active frame at `rows` terminal rows instead of the terminal
+    /// height.
+    ///
+    /// When the tree doesn't fit, active and deeply nested tasks are kept,
+    /// the rest are skipped (their [`Renderer::render_task`] isn't called),
+    /// and [`Renderer::render_overflow_line`] reports how many were hidden.
+    pub fn max_height(mut self, rows: usize) -> Self {
+        self.options.height = Some(rows);
+        self
+    }
+
+    /// Cut lines wider than the terminal with an ellipsis instead of letting
+    /// them wrap. Escape sequences are preserved. Disabled by default.
+    pub fn truncate_lines(mut self, yes: bool) -> Self {
+        self.options.truncate = yes;
+        self
+    }
+
+    /// Print finished tasks up to `depth` levels deep once above the frame
+    /// and drop them from the live tree.
+    ///
+    /// Defaults to 1: only root tasks move to the scrollback, so a program
+    /// running inside a single top-level span keeps its whole tree live.
+    /// With `commit_depth(2)`, each finished child of that span is printed
+    /// as it completes, like cargo's `Compiling …` lines above its progress
+    /// bar.
+    pub fn commit_depth(mut self, depth: usize) -> Self {
+        self.set_commit_depth(depth);
+        self
+    }
+
+    pub(crate) fn set_commit_depth(&mut self, depth: usize) {
+        self.commit = Commit::Depth(depth);
+    }
+
+    /// Like [`commit_depth`](Self::commit_depth), but commits the finished
+    /// tasks for which `f` returns `true`. Finished root tasks are always
+    /// committed.
+    ///
+    /// ```rust,ignore
+    /// // Print completed work as it finishes, but keep cancelled tasks live.
+    /// let renderer = TaskRenderer::new(MyRenderer::default())
+    ///     .commit_when(|task| !task.cancelled());
+    /// ```
+    pub fn commit_when(
+        mut self,
+        f: impl Fn(&TaskView<'_, R>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.set_commit_when(f);
+        self
+    }
+
+    pub(crate) fn set_commit_when(
+        &mut self,
+        f: impl Fn(&TaskView<'_, R>) -> bool + Send + Sync + 'static,
+    ) {
+        self.commit = Commit::When(Arc::new(f));
+    }
+
+    /// Whether frames are redrawn in place (as opposed to appended).
+    pub(crate) fn is_interactive(&self) -> bool {
+        self.mode == OutputMode::Interactive
+    }
+
     /// Applies a single [`Action`] to the task tree.
     pub fn update(&mut self, action: Action<R>) {
+        // Note what changes before applying, while the old state is visible
+        let is_active = |id: &TaskId| {
+            let task = self.tasks.tasks.get(id);
+            task.is_some_and(|task| !task.completed && !task.cancelled)
+        };
+        let started = match &action {
+            Action::TaskStart { id, .. } => Some(*id),
+            _ => None,
+        };
+        let cancelled = matches!(action, Action::CancelAll);
+        let ended = match &action {
+            Action::TaskEnd { id } if is_active(id) => vec![*id],
+            Action::CancelAll => {
+                let mut ended = Vec::new();
+                self.active_subtree(TaskId::ROOT, &mut ended);
+                ended
+            }
+            _ => Vec::new(),
+        };
+        let event_task = match &action {
+            Action::Event { parent, .. } => Some(parent.unwrap_or(TaskId::ROOT)),
+            _ => None,
+        };
+        let last_seq = |tasks: &TaskStore<R>, id| tasks.tasks.get(&id)?.last_event_seq();
+        let seq_before = event_task.and_then(|id| last_seq(&self.tasks, id));
+
         self.tasks.apply(action);
+
+        if started.is_some() {
+            self.stats.task_started();
+        }
+        for &id in &ended {
+            let view = TaskView::new(&self.tasks, id);
+            self.stats.task_ended(&self.r, &view, cancelled);
+        }
+        let event = event_task
+            .and_then(|id| Some((id, last_seq(&self.tasks, id)?)))
+            .filter(|(_, seq)| Some(*seq) != seq_before);
+        // With no room for events, the new one is dropped as soon as it's buffered.
+        let event = event.and_then(|(id, seq)| Some((id, self.tasks.task(&id).event_index(seq)?)));
+        if let Some((id, index)) = event {
+            self.stats
+                .event(&self.r, &EventView::new(&self.tasks, id, index));
+        }
+
+        if self.mode == OutputMode::Append {
+            let result = self.journal(started, &ended, event);
+            if let Err(err) = result {
+                self.journal_error.get_or_insert(err.kind());
+            }
+        }
+    }
+
+    /// Renders the lines an action produces in [`OutputMode::Append`] right
+    /// away, while the task state and event buffer still match it.
+    fn journal(
+        &mut self,
+        started: Option<TaskId>,
+        ended: &[TaskId],
+        event: Option<(TaskId, usize)>,
+    ) -> Result<(), std::io::Error> {
+        if started.is_none() && ended.is_empty() && event.is_none() {
+            return Ok(());
+        }
+        let options = *self
+            .journal_options
+            .get_or_insert_with(|| self.options.resolve());
+        let _scope = Self::widget_scope(&options);
+        let mut out = Vec::new();
+        let mut t = FrameWriter::new(&mut out, 0, options);
+        for id in started.iter().chain(ended) {
+            let task = self.tasks.tasks.get(id);
+            if task.is_some_and(|task| task.data.is_some()) {
+                let view = TaskView::new(&self.tasks, *id);
+                self.r.render_task_line(&mut t, &view)?;
+            }
+        }
+        if let Some((id, index)) = event {
+            let view = EventView::new(&self.tasks, id, index);
+            self.r.render_event_line(&mut t, &view)?;
+        }
+        t.flush()?;
+        self.journal.extend(out);
+        Ok(())
+    }
+
+    /// Totals for the run so far. See [`RunSummary`].
+    pub fn summary(&self) -> RunSummary {
+        self.stats.summary()
+    }
+
+    /// Prints `summary` with [`Renderer::render_run_summary`] below the
+    /// current frame, leaving the frame in place as permanent output.
+    pub fn render_summary(
+        &mut self,
+        target: &mut dyn Write,
+        summary: &RunSummary,
+    ) -> Result<(), std::io::Error> {
+        let options = self.options.resolve();
+        let _scope = Self::widget_scope(&options);
+        let mut f = FrameWriter::new(target, 0, options);
+        self.r.render_run_summary(&mut f, summary)?;
+        f.flush()?;
+        self.frame.clear();
+        self.frame_lines = 0;
+        Ok(())
+    }
+
+    /// Collects active tasks below `id`, children before their parents.
+    fn active_subtree(&self, id: TaskId, out: &mut Vec<TaskId>) {
+        let task = self.tasks.task(&id);
+        for &subtask in task.subtasks() {
+            self.active_subtree(subtask, out);
+        }
+        if !id.is_root() && !task.completed && !task.cancelled {
+            out.push(id);
+        }
     }
 
     /// Renders the current task tree to `target`.
@@ -87,67 +454,356 @@ impl<R: Renderer> TaskRenderer<R> {
     /// Completed and cancelled root tasks are rendered first (and removed),
     /// then active root tasks are rendered in the erasable frame region.
     pub fn render(&mut self, target: &mut dyn Write) -> Result<(), std::io::Error> {
+        let options = self.options.resolve();
+        let _scope = Self::widget_scope(&options);
+        let size = (options.width.unwrap_or(term::DEFAULT_WIDTH), options.height);
+        if self.size != Some(size) {
+            self.size = Some(size);
+            self.r.on_resize(size.0, size.1);
+        }
         self.r.on_render_start();
+        match self.mode {
+            OutputMode::Append => self.render_append(target, options)?,
+            _ => self.render_frame(target, options)?,
+        }
+        self.r.on_render_end();
+        Ok(())
+    }
+
+    fn render_frame(
+        &mut self,
+        target: &mut dyn Write,
+        options: FrameOptions,
+    ) -> Result<(), std::io::Error> {
+        self.tasks.hidden.clear();
+
+        // Root events and committed tasks are printed once, above the frame
+        let mut permanent = Vec::new();
+        let mut t = FrameWriter::new(&mut permanent, 0, options);
+        let queue = self.flush_finished(&mut t)?;
+        t.flush()?;
+        let width = t.width();
+
+        // Leave a row for the cursor below the frame so it can be erased
+        let budget = options.height.map(|rows| rows.saturating_sub(1));
+        let hidden = match budget {
+            Some(budget) => self.clip(&queue, budget),
+            None => 0,
+        };
 
-        // Move the cursor to top of the active tasks frame
-        let mut t = FrameWriter::new(target, self.frame_lines);
+        // Render the active task frame into a buffer
+        let mut buf = Vec::new();
+        let mut t = FrameWriter::new(&mut buf, 0, options);
+        if let Some(budget) = budget {
+            t.clip_rows(budget);
+        }
+        self.render_roots(&mut t, queue, hidden)?;
+        t.flush()?;
+        let buffered_lines = t.frame_lines();
+
+        // Split the frame into lines; a frame ending mid-line can't be diffed
+        let text = String::from_utf8_lossy(&buf);
+        let lines = match text.is_empty() {
+            true => Some(Vec::new()),
+            false => text
+                .strip_suffix('\n')
+                .map(|body| body.split('\n').map(String::from).collect::<Vec<_>>()),
+        };
+
+        // Anything printed above the frame, a width change, or a frame that
+        // can't be diffed means starting from a clean slate
+        let drawn = self
+            .frame
+            .iter()
+            .map(|l| text::rows(l, width))
+            .sum::<usize>();
+        let stale = drawn != self.frame_lines || width != self.frame_width;
+        let full = !permanent.is_empty() || stale || lines.is_none();
+        let cleared_lines = self.drawn_rows(width);
+        if full {
+            self.frame.clear();
+            self.frame_width = width;
+        }
+
+        let mut out = Vec::new();
+        match lines {
+            Some(lines) => {
+                redraw(&mut out, &self.frame, &lines, width);
+                self.frame_lines = lines.iter().map(|l| text::rows(l, width)).sum();
+                self.frame = lines;
+            }
+            None => {
+                out.extend_from_slice(&buf);
+                self.frame_lines = buffered_lines;
+            }
+        }
+        if !full && out.is_empty() {
+            return Ok(());
+        }
+
+        if self.synchronized {
+            target.write_all(term::SYNC_BEGIN)?;
+        }
+        let mut body = || {
+            if full {
+                let mut t = FrameWriter::new(target, cleared_lines, options);
+                t.clear_frame()?;
+                target.write_all(&permanent)?;
+            }
+            target.write_all(&out)
+        };
+        let mut result = body();
+        // End the update even after a failed write, or the screen stays frozen.
+        if self.synchronized {
+            result = result.and(target.write_all(term::SYNC_END));
+        }
+        result?;
+        target.flush()
+    }
+
+    /// Prints everything recorded since the last render, then drops finished
+    /// root tasks. Never moves the cursor.
+    fn render_append(
+        &mut self,
+        target: &mut dyn Write,
+        options: FrameOptions,
+    ) -> Result<(), std::io::Error> {
+        let mut t = FrameWriter::new(target, 0, options);
+        self.journal_options = None;
+
+        if let Some(kind) = self.journal_error.take() {
+            self.journal.clear();
+            return Err(kind.into());
+        }
+        t.write_all(&std::mem::take(&mut self.journal))?;
+        self.flush_root_suppressed(&mut t)?;
+
+        self.tasks.root().clear_events();
+        for id in self.committed() {
+            self.tasks.remove(id);
+        }
+
+        t.flush()
+    }
+
+    /// Erases the active frame, runs `f` with direct access to `target`, then
+    /// redraws the frame below whatever `f` wrote.
+    ///
+    /// Use this to interleave ordinary output (`println!`-style messages,
+    /// interactive prompts) with the task tree without corrupting it.
+    ///
+    /// ```rust,ignore
+    /// let mut stderr = std::io::stderr();
+    /// renderer.suspend(&mut stderr, |w| writeln!(w, "warning: disk almost full"))??;
+    /// ```
+    pub fn suspend<T>(
+        &mut self,
+        target: &mut dyn Write,
+        f: impl FnOnce(&mut dyn Write) -> T,
+    ) -> Result<T, std::io::Error> {
+        let options = self.options.resolve();
+        let cleared_lines = self.drawn_rows(options.width.unwrap_or(term::DEFAULT_WIDTH));
+        let mut t = FrameWriter::new(target, cleared_lines, options);
         t.clear_frame()?;
+        self.frame_lines = 0;
+        self.frame.clear();
+
+        let out = f(target);
+        target.flush()?;
+
+        self.render(target)?;
+        Ok(out)
+    }
+
+    /// Rows the drawn frame may occupy once the terminal is `width` wide.
+    ///
+    /// After a resize the terminal may have rewrapped the frame's lines, so
+    /// this errs on the side of clearing more rows rather than leaving a
+    /// partial frame behind.
+    fn drawn_rows(&self, width: usize) -> usize {
+        if width == self.frame_width {
+            return self.frame_lines;
+        }
+        let rewrapped = self.frame.iter().map(|l| text::rows(l, width)).sum();
+        self.frame_lines.max(rewrapped)
+    }
 
-        // Render root task
-        let mut queue = self.flush_root(&mut t)?;
+    /// Renders the current task tree as styled [`Line`]s, for drawing it
+    /// inside another UI or asserting on it in tests.
+    ///
+    /// Unlike [`render`](Self::render), nothing is written to a terminal and
+    /// the tree is left as it is: finished tasks aren't committed and root
+    /// events aren't printed. Lines are cut to `width` columns, and a tree
+    /// taller than `height` rows is clipped like the live frame.
+    ///
+    /// ```rust,ignore
+    /// for line in renderer.render_lines(area.width, area.height)? {
+    ///     for (text, style) in line.styled() {
+    ///         pane.draw(text, style);
+    ///     }
+    /// }
+    /// ```
+    pub fn render_lines(&mut self, width: usize, height: usize) -> std::io::Result<Vec<Line>> {
+        let options = FrameOptions {
+            width: Some(width),
+            height: Some(height),
+            truncate: true,
+            color: Some(ColorSupport::TrueColor),
+            glyphs: self.options.glyphs,
+        };
+        let _scope = Self::widget_scope(&options);
+        self.r.on_render_start();
+        self.tasks.hidden.clear();
+        let roots = self.tasks.task(&TaskId::ROOT).subtasks();
+        let roots = roots.iter().copied().collect::<VecDeque<_>>();
+        let hidden = self.clip(&roots, height);
 
-        // Start active task frame
-        let mut t = FrameWriter::new(target, 0);
-        while let Some(task) = queue.pop_front() {
+        let mut buf = Vec::new();
+        let mut t = FrameWriter::new(&mut buf, 0, options);
+        t.clip_rows(height);
+        self.render_roots(&mut t, roots, hidden)?;
+        t.flush()?;
+        self.tasks.hidden.clear();
+        self.r.on_render_end();
+        Ok(offscreen::lines(&String::from_utf8_lossy(&buf)))
+    }
+
+    /// Renders the visible tasks of `roots`, followed by the overflow line
+    /// when [`clip`](Self::clip) hid `hidden` of them.
+    fn render_roots(
+        &mut self,
+        t: &mut FrameWriter<'_>,
+        mut roots: VecDeque<TaskId>,
+        hidden: usize,
+    ) -> Result<(), std::io::Error> {
+        roots.retain(|id| !self.tasks.hidden.contains(id));
+        while let Some(task) = roots.pop_front() {
             if self.tasks.task(&task).data.is_some() {
                 let view = TaskView::new(&self.tasks, task);
-                self.r.render_task(&mut t, &view)?;
+                self.r.render_task(t, &view)?;
             }
         }
+        if hidden > 0 {
+            self.r.render_overflow_line(t, hidden)?;
+        }
+        Ok(())
+    }
 
-        // Store the number of lines drawn in the active task frame
-        self.frame_lines = t.frame_lines();
+    /// Chooses which tasks under `roots` fit in `budget` rows, marking the
+    /// rest hidden. Returns the number of hidden tasks.
+    ///
+    /// Active tasks are placed first, deepest first, each together with the
+    /// ancestors needed to reach it. Completed tasks fill any space left.
+    fn clip(&mut self, roots: &VecDeque<TaskId>, budget: usize) -> usize {
+        let mut order = Vec::new();
+        let mut stack = roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            let task = self.tasks.task(&id);
+            if task.data.is_some() {
+                order.push(id);
+            }
+            stack.extend(task.subtasks().iter().rev());
+        }
 
-        t.flush()?;
+        let heights = order
+            .iter()
+            .map(|&id| (id, self.r.task_height(&TaskView::new(&self.tasks, id))))
+            .collect::<HashMap<_, _>>();
+        if heights.values().sum::<usize>() <= budget {
+            return 0;
+        }
 
-        self.r.on_render_end();
+        // Reserve a row for the overflow line
+        let budget = budget.saturating_sub(1);
+        let mut candidates = order.iter().enumerate().collect::<Vec<_>>();
+        candidates.sort_by_key(|&(i, id)| {
+            let view = TaskView::new(&self.tasks, *id);
+            (!view.active(), Reverse(view.depth()), i)
+        });
+
+        let mut visible = HashSet::new();
+        let mut used = 0;
+        for (_, &id) in candidates {
+            let mut path = Vec::new();
+            let mut current = Some(id);
+            while let Some(id) = current.filter(|id| !id.is_root() && !visible.contains(id)) {
+                path.push(id);
+                current = self.tasks.task(&id).parent;
+            }
+            let cost = path.iter().filter_map(|id| heights.get(id)).sum::<usize>();
+            if used + cost <= budget {
+                used += cost;
+                visible.extend(path);
+            }
+        }
+
+        self.tasks.hidden = order
+            .into_iter()
+            .filter(|id| !visible.contains(id))
+            .collect();
+        self.tasks.hidden.len()
+    }
+
+    /// Finished tasks to move to the scrollback, in tree order. Descendants
+    /// of a committed task aren't listed separately.
+    fn committed(&self) -> Vec<TaskId> {
+        let mut committed = Vec::new();
+        let mut stack = self
+            .tasks
+            .task(&TaskId::ROOT)
+            .subtasks()
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            let view = TaskView::new(&self.tasks, id);
+            if !view.active() && self.commit.applies(&view) {
+                committed.push(id);
+            } else {
+                stack.extend(self.tasks.task(&id).subtasks().iter().rev());
+            }
+        }
+        committed
+    }
 
+    /// Prints the marker for events suppressed outside any task, which has
+    /// no line in the frame to sit under, and resets the count.
+    fn flush_root_suppressed(
+        &mut self,
+        target: &mut FrameWriter<'_>,
+    ) -> Result<(), std::io::Error> {
+        if self.tasks.task(&TaskId::ROOT).suppressed > 0 {
+            let view = TaskView::new(&self.tasks, TaskId::ROOT);
+            self.r.render_suppressed_line(target, &view)?;
+            self.tasks.root().suppressed = 0;
+        }
         Ok(())
     }
 
-    fn flush_root(
+    /// Prints root events and committed tasks to `target`, removes them from
+    /// the tree, and returns the remaining root tasks.
+    fn flush_finished(
         &mut self,
         target: &mut FrameWriter<'_>,
     ) -> Result<VecDeque<TaskId>, std::io::Error> {
-        let mut active = VecDeque::new();
-        let mut completed = VecDeque::new();
-
         for i in 0..self.tasks.task(&TaskId::ROOT).events().len() {
             let view = EventView::new(&self.tasks, TaskId::ROOT, i);
             self.r.render_event_line(target, &view)?;
         }
         self.tasks.root().clear_events();
+        self.flush_root_suppressed(target)?;
 
-        for i in 0..self.tasks.task(&TaskId::ROOT).subtasks().len() {
-            let task = *self
-                .tasks
-                .task(&TaskId::ROOT)
-                .subtasks()
-                .get_index(i)
-                .unwrap();
-            if self.tasks.task(&task).completed || self.tasks.task(&task).cancelled {
-                let view = TaskView::new(&self.tasks, task);
-                self.r.render_task(target, &view)?;
-                completed.push_back(task);
-            } else {
-                active.push_back(task);
-            }
+        let committed = self.committed();
+        for &id in &committed {
+            let view = TaskView::new(&self.tasks, id);
+            self.r.render_task(target, &view)?;
         }
-
-        for id in completed {
+        for id in committed {
             self.tasks.remove(id);
         }
 
-        Ok(active)
+        let roots = self.tasks.task(&TaskId::ROOT).subtasks();
+        Ok(roots.iter().copied().collect())
     }
 }